@@ -1,5 +1,6 @@
-use crate::block::BlockError::{DimensionMismatch, EmptyBlock};
+use crate::block::BlockError::{DimensionMismatch, Disconnected, EmptyBlock};
 use nalgebra::DMatrix;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -13,11 +14,112 @@ pub(crate) enum BlockError {
     DimensionMismatch,
     #[error("Empty block")]
     EmptyBlock,
+    #[error("Block cells are not connected")]
+    Disconnected,
+    #[error("Invalid run length {0:?}")]
+    InvalidRunLength(char),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Conventional names of the standard Blokus pieces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum PieceName {
+    I1,
+    I2,
+    I3,
+    V3,
+    I4,
+    L4,
+    T4,
+    O4,
+    Z4,
+    I5,
+    L5,
+    N5,
+    P5,
+    U5,
+    Y5,
+    T5,
+    V5,
+    W5,
+    Z5,
+    F5,
+    X5,
+}
+
+impl PieceName {
+    /// All the names, in [`Block::default_block_set`] order.
+    pub const ALL: [PieceName; 21] = [
+        PieceName::I1,
+        PieceName::I2,
+        PieceName::I3,
+        PieceName::V3,
+        PieceName::I4,
+        PieceName::L4,
+        PieceName::T4,
+        PieceName::O4,
+        PieceName::Z4,
+        PieceName::I5,
+        PieceName::L5,
+        PieceName::N5,
+        PieceName::P5,
+        PieceName::U5,
+        PieceName::Y5,
+        PieceName::T5,
+        PieceName::V5,
+        PieceName::W5,
+        PieceName::Z5,
+        PieceName::F5,
+        PieceName::X5,
+    ];
+
+    /// Letter and size of the piece, e.g. `"Y-pentomino"`, `"monomino"`.
+    pub fn long_name(&self) -> String {
+        let short_name = format!("{:?}", self);
+        let (letter, cells) = short_name.split_at(1);
+        let size = match cells {
+            "1" => return String::from("monomino"),
+            "2" => return String::from("domino"),
+            "3" => "tromino",
+            "4" => "tetromino",
+            _ => "pentomino",
+        };
+        format!("{}-{}", letter, size)
+    }
+}
+
+impl fmt::Display for PieceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Rows of `#` (filled) and `_` (empty) cells, parsable by [`Block::from_str`].
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.nrows() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.ncols() {
+                write!(f, "{}", if self.data[(row, col)] { '#' } else { '_' })?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Block {
     pub fn from_str(s: &str) -> Result<Self, BlockError> {
-        let lines: Vec<String> = s.lines().map(String::from).collect();
+        let lines: Vec<&str> = s.lines().collect();
+        // ignore leading and trailing empty lines, e.g. from a text file
+        let first_line = lines.iter().position(|line| !line.is_empty()).unwrap_or(0);
+        let last_line = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |i| i + 1);
+        let lines = &lines[first_line..last_line.max(first_line)];
         let rows: Vec<Vec<bool>> = lines
             .iter()
             .map(|line| line.chars().map(|c| matches!(c, '#')).collect())
@@ -41,6 +143,40 @@ impl Block {
         })
     }
 
+    /// Parse a run-length encoded block: rows separated by `/`, each digit being the length
+    /// of a run, alternating empty and filled runs starting with an empty one.
+    /// Rows shorter than the longest one end with empty cells.
+    ///
+    /// E.g. `01/01/02` is the L tetromino `#_`, `#_`, `##`.
+    pub fn from_rle(s: &str) -> Result<Self, BlockError> {
+        let rows = s
+            .trim()
+            .split('/')
+            .map(|runs| {
+                let mut row = String::new();
+                for (run, c) in runs.chars().enumerate() {
+                    let length = c.to_digit(10).ok_or(BlockError::InvalidRunLength(c))? as usize;
+                    let cell = if run % 2 == 0 { "_" } else { "#" };
+                    row.push_str(&cell.repeat(length));
+                }
+                Ok(row)
+            })
+            .collect::<Result<Vec<String>, BlockError>>()?;
+        let ncols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let grid: Vec<String> = rows
+            .iter()
+            .map(|row| format!("{:_<width$}", row, width = ncols))
+            .collect();
+        let block = Block::from_str(&grid.join("\n"))?;
+        if block.cells() == 0 {
+            return Err(EmptyBlock);
+        }
+        if !block.is_connected() {
+            return Err(Disconnected);
+        }
+        Ok(block)
+    }
+
     pub fn nrows(&self) -> usize {
         self.data.nrows()
     }
@@ -53,11 +189,149 @@ impl Block {
         self.data.iter().map(|b| if *b { 1 } else { 0 }).sum()
     }
 
+    /// `(nrows, ncols)` of the smallest box containing the filled cells.
+    pub fn bounding_box(&self) -> (usize, usize) {
+        let filled = |row: usize, col: usize| self.data[(row, col)];
+        let rows: Vec<usize> = (0..self.nrows())
+            .filter(|&row| (0..self.ncols()).any(|col| filled(row, col)))
+            .collect();
+        let cols: Vec<usize> = (0..self.ncols())
+            .filter(|&col| (0..self.nrows()).any(|row| filled(row, col)))
+            .collect();
+        match (rows.first(), rows.last(), cols.first(), cols.last()) {
+            (Some(top), Some(bottom), Some(left), Some(right)) => {
+                (bottom - top + 1, right - left + 1)
+            }
+            _ => (0, 0),
+        }
+    }
+
+    /// Block of `nrows` x `ncols` cells with the filled cells of this block centered in it,
+    /// rounding toward the top left, e.g. to draw every piece of a hand in same size slots.
+    ///
+    /// Fails with [`BlockError::DimensionMismatch`] if the filled cells do not fit.
+    pub fn padded_to(&self, nrows: usize, ncols: usize) -> Result<Block, BlockError> {
+        let filled_cells: Vec<(usize, usize)> = (0..self.nrows())
+            .flat_map(|row| (0..self.ncols()).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cell_at_row_col(row, col))
+            .collect();
+        let (height, width) = self.bounding_box();
+        if height > nrows || width > ncols {
+            return Err(DimensionMismatch);
+        }
+        let top = filled_cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let left = filled_cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+        let row_offset = (nrows - height) / 2;
+        let col_offset = (ncols - width) / 2;
+        let mut data = DMatrix::from_element(nrows, ncols, false);
+        for (row, col) in filled_cells {
+            data[(row - top + row_offset, col - left + col_offset)] = true;
+        }
+        Ok(Block { data })
+    }
+
+    /// `(cells, min_dimension, max_dimension)` of the filled cells, the same for every
+    /// orientation: a cheap filter before searching placements.
+    pub fn shape_signature(&self) -> (usize, usize, usize) {
+        let (nrows, ncols) = self.bounding_box();
+        (self.cells(), nrows.min(ncols), nrows.max(ncols))
+    }
+
+    /// Ratio of the bounding box filled by the block, 1.0 for rectangular blocks.
+    pub fn area_bounding_ratio(&self) -> f32 {
+        let (nrows, ncols) = self.bounding_box();
+        match nrows * ncols {
+            0 => 0.0,
+            area => self.cells() as f32 / area as f32,
+        }
+    }
+
+    /// Number of convex corners of the block outline: for each filled cell, the diagonal
+    /// directions whose two orthogonal neighbors are empty.
+    ///
+    /// Each convex corner is a potential diagonal growth point once the block is placed,
+    /// 4 for straight blocks and more for branching ones.
+    #[cfg(test)]
+    pub fn convex_corner_count(&self) -> usize {
+        let empty = |row: i32, col: i32| {
+            row < 0
+                || col < 0
+                || row >= self.nrows() as i32
+                || col >= self.ncols() as i32
+                || !self.cell_at_row_col(row as usize, col as usize)
+        };
+        let mut count = 0;
+        for row in 0..self.nrows() as i32 {
+            for col in 0..self.ncols() as i32 {
+                if empty(row, col) {
+                    continue;
+                }
+                for (drow, dcol) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                    if empty(row + drow, col) && empty(row, col + dcol) {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    /// Mean `(row, col)` of the filled cells.
+    pub fn centroid(&self) -> (f32, f32) {
+        let mut row_sum = 0;
+        let mut col_sum = 0;
+        for row in 0..self.nrows() {
+            for col in 0..self.ncols() {
+                if self.cell_at_row_col(row, col) {
+                    row_sum += row;
+                    col_sum += col;
+                }
+            }
+        }
+        let cells = self.cells().max(1) as f32;
+        (row_sum as f32 / cells, col_sum as f32 / cells)
+    }
+
     pub fn transpose(&self) -> Block {
         Block {
             data: self.data.transpose(),
         }
     }
+    /// Reflection across the anti-diagonal (transpose then rotate 180).
+    #[cfg(test)]
+    pub fn anti_transpose(&self) -> Block {
+        self.transpose().rotate_90().rotate_90()
+    }
+
+    /// The 8 orientations of the block: {id, transpose} x {id, rot90, rot180, rot270}.
+    ///
+    /// Symmetric blocks yield duplicates.
+    pub fn orientations(&self) -> Vec<Block> {
+        [self.clone(), self.transpose()]
+            .into_iter()
+            .flat_map(|block| {
+                let rotate_90 = block.rotate_90();
+                let rotate_180 = rotate_90.rotate_90();
+                let rotate_270 = rotate_180.rotate_90();
+                [block, rotate_90, rotate_180, rotate_270]
+            })
+            .collect()
+    }
+
+    /// Whether both blocks are the same piece, up to orientation.
+    pub fn is_same_piece(&self, other: &Block) -> bool {
+        self.cells() == other.cells() && self.orientations().contains(other)
+    }
+
+    /// Conventional name of the block if it is a standard piece, whatever its orientation.
+    pub fn standard_name(&self) -> Option<PieceName> {
+        Block::default_block_set()
+            .iter()
+            .zip(PieceName::ALL)
+            .find(|(block, _)| block.is_same_piece(self))
+            .map(|(_, name)| name)
+    }
+
     pub fn rotate_90(&self) -> Block {
         let transposed = self.data.transpose();
         let rows: Vec<Vec<bool>> = transposed
@@ -83,6 +357,110 @@ impl Block {
         self.data[(row, col)]
     }
 
+    /// Whether `other`, with its top left corner at `(row_offset, col_offset)` relative to
+    /// this block top left corner, shares a filled cell with this block.
+    #[cfg(test)]
+    pub fn overlaps(&self, other: &Block, row_offset: i32, col_offset: i32) -> bool {
+        (0..other.nrows()).any(|row| {
+            (0..other.ncols()).any(|col| {
+                let self_row = row as i32 + row_offset;
+                let self_col = col as i32 + col_offset;
+                other.data[(row, col)]
+                    && self_row >= 0
+                    && self_col >= 0
+                    && (self_row as usize) < self.nrows()
+                    && (self_col as usize) < self.ncols()
+                    && self.data[(self_row as usize, self_col as usize)]
+            })
+        })
+    }
+
+    /// Filled cells of both blocks, `other` being offset like in [`Block::overlaps`].
+    ///
+    /// The result grows to hold both blocks, gaps are empty cells.
+    #[cfg(test)]
+    pub fn union(&self, other: &Block, row_offset: i32, col_offset: i32) -> Block {
+        let top = row_offset.min(0);
+        let left = col_offset.min(0);
+        let bottom = (self.nrows() as i32).max(row_offset + other.nrows() as i32);
+        let right = (self.ncols() as i32).max(col_offset + other.ncols() as i32);
+        let mut data =
+            DMatrix::from_element((bottom - top) as usize, (right - left) as usize, false);
+        for (block, block_row_offset, block_col_offset) in
+            [(self, 0, 0), (other, row_offset, col_offset)]
+        {
+            for row in 0..block.nrows() {
+                for col in 0..block.ncols() {
+                    if block.data[(row, col)] {
+                        let union_row = (row as i32 + block_row_offset - top) as usize;
+                        let union_col = (col as i32 + block_col_offset - left) as usize;
+                        data[(union_row, union_col)] = true;
+                    }
+                }
+            }
+        }
+        Block { data }
+    }
+
+    /// Whether every filled cell can be reached from any other one through side neighbors.
+    pub fn is_connected(&self) -> bool {
+        let filled: Vec<(usize, usize)> = (0..self.nrows())
+            .flat_map(|row| (0..self.ncols()).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.data[(row, col)])
+            .collect();
+        let Some(&start) = filled.first() else {
+            return false;
+        };
+        let mut reached = vec![start];
+        let mut to_visit = vec![start];
+        while let Some((row, col)) = to_visit.pop() {
+            for neighbor in [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ] {
+                if filled.contains(&neighbor) && !reached.contains(&neighbor) {
+                    reached.push(neighbor);
+                    to_visit.push(neighbor);
+                }
+            }
+        }
+        reached.len() == filled.len()
+    }
+
+    /// Parse a piece set: blocks as accepted by [`Block::from_str`], or single line blocks
+    /// starting with a digit as accepted by [`Block::from_rle`], separated by blank lines,
+    /// with `\n` or `\r\n` line endings.
+    pub fn set_from_str(s: &str) -> Result<Vec<Self>, BlockError> {
+        let lines: Vec<&str> = s.lines().collect();
+        let mut blocks = vec![];
+        for block_lines in lines
+            .split(|line| line.is_empty())
+            .filter(|block_lines| block_lines.iter().any(|line| !line.trim().is_empty()))
+        {
+            let block = match block_lines {
+                [line] if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) => {
+                    Block::from_rle(line)?
+                }
+                _ => Block::from_str(&block_lines.join("\n"))?,
+            };
+            if !block.is_connected() {
+                return Err(Disconnected);
+            }
+            blocks.push(block);
+        }
+        if blocks.is_empty() {
+            return Err(EmptyBlock);
+        }
+        Ok(blocks)
+    }
+
+    /// Read a piece set file, see [`Block::set_from_str`].
+    pub fn set_from_file(path: impl AsRef<std::path::Path>) -> Result<Vec<Self>, BlockError> {
+        Block::set_from_str(&std::fs::read_to_string(path)?)
+    }
+
     pub fn default_block_set() -> Vec<Self> {
         vec![
             // 1
@@ -185,10 +563,59 @@ impl Block {
     }
 }
 
+/// The 8 orientations of a block, computed once.
+///
+/// Orientation `transposition * 4 + rotation` is the block transposed `transposition`
+/// times then rotated clockwise `rotation` times, like in a `BlockPlacement`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct OrientedPiece {
+    pub blocks: [Block; 8],
+    /// indexes of the unique orientations, in increasing order
+    pub distinct: Vec<usize>,
+}
+
+impl OrientedPiece {
+    pub fn new(block: &Block) -> Self {
+        let blocks: [Block; 8] = block.orientations().try_into().expect("8 orientations");
+        let distinct = (0..blocks.len())
+            .filter(|&i| !blocks[..i].contains(&blocks[i]))
+            .collect();
+        OrientedPiece { blocks, distinct }
+    }
+
+    pub fn index(rotation: u8, transposition: u8) -> usize {
+        usize::from(transposition % 2) * 4 + usize::from(rotation % 4)
+    }
+
+    /// `(rotation, transposition)` of orientation `index`.
+    pub fn rotation_transposition(index: usize) -> (u8, u8) {
+        ((index % 4) as u8, (index / 4 % 2) as u8)
+    }
+
+    pub fn get(&self, rotation: u8, transposition: u8) -> &Block {
+        &self.blocks[OrientedPiece::index(rotation, transposition)]
+    }
+
+    /// Position in `distinct` of the orientation looking like orientation `index`.
+    pub fn distinct_position(&self, index: usize) -> usize {
+        self.distinct
+            .iter()
+            .position(|&i| self.blocks[i] == self.blocks[index % 8])
+            .expect("every orientation has a distinct equivalent")
+    }
+
+    /// Next distinct orientation after orientation `index`, wrapping around.
+    pub fn next_distinct(&self, index: usize) -> usize {
+        self.distinct[(self.distinct_position(index) + 1) % self.distinct.len()]
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::block::Block;
+    use crate::block::{Block, BlockError, OrientedPiece, PieceName};
     use crate::board::Board;
+    use crate::strategy::BlockPlacement;
+    use std::collections::HashSet;
 
     #[test]
     fn test_block_from_str() {
@@ -206,6 +633,74 @@ mod test {
         test_valid_block("#\n#", 2, 1);
         test_valid_block("# \n##", 2, 2);
         test_valid_block(" #\n##", 2, 2);
+
+        assert!(Block::from_str("").is_err());
+        assert!(Block::from_str("\n\n").is_err());
+        assert!(Block::from_str("#\n\n##").is_err());
+        assert!(Block::from_str("#\n##\n").is_err());
+    }
+
+    #[test]
+    fn test_block_display() {
+        for block in Block::default_block_set() {
+            assert_eq!(Block::from_str(&block.to_string()).unwrap(), block);
+        }
+        assert_eq!(Block::from_str("# \n##").unwrap().to_string(), "#_\n##");
+    }
+
+    #[test]
+    fn test_block_set_from_file() {
+        let path = std::env::temp_dir().join("blockus-rs-test-pieces.txt");
+        std::fs::write(&path, "\n##\n#_\n\n\n###\n\n").unwrap();
+        let blocks = Block::set_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                Block::from_str("##\n#_").unwrap(),
+                Block::from_str("###").unwrap()
+            ]
+        );
+
+        // run-length encoded pieces, e.g. exported from another tool
+        assert_eq!(Block::set_from_str("02/01\n\n03\n").unwrap(), blocks);
+        assert!(matches!(
+            Block::set_from_str("01/11\n\n###"),
+            Err(BlockError::Disconnected)
+        ));
+        assert!(matches!(
+            Block::set_from_str("##\n\n0x3"),
+            Err(BlockError::InvalidRunLength('x'))
+        ));
+        // written on Windows
+        assert_eq!(
+            Block::set_from_str("##\r\n#_\r\n\r\n###\r\n").unwrap(),
+            blocks
+        );
+        assert!(matches!(
+            Block::set_from_str("#_#"),
+            Err(BlockError::Disconnected)
+        ));
+        assert!(matches!(
+            Block::set_from_str("\n\n"),
+            Err(BlockError::EmptyBlock)
+        ));
+        assert!(matches!(
+            Block::set_from_file("/nonexistent/pieces.txt"),
+            Err(BlockError::Io(_))
+        ));
+        assert!(Block::default_block_set().iter().all(|b| b.is_connected()));
+        assert!(!Block::from_str("#_\n_#").unwrap().is_connected());
+    }
+
+    #[test]
+    fn test_block_from_str_blank_lines() {
+        let expected = Block::from_str("###").unwrap();
+        assert_eq!(Block::from_str("###\n").unwrap(), expected);
+        assert_eq!(Block::from_str("###\n\n").unwrap(), expected);
+        assert_eq!(Block::from_str("\n###\n\n\n").unwrap(), expected);
+        assert_eq!(Block::from_str("###\r\n\r\n").unwrap(), expected);
+        assert_eq!(Block::from_str("###\n\n").unwrap().nrows(), 1);
     }
 
     #[test]
@@ -225,6 +720,310 @@ mod test {
             Block::from_str(" #\n #\n##").unwrap()
         );
     }
+    #[test]
+    fn test_anti_transpose() {
+        let b = Block::from_str("#  \n###").unwrap();
+        assert_eq!(b.anti_transpose(), Block::from_str("# \n# \n##").unwrap());
+        assert_eq!(b, b.anti_transpose().anti_transpose());
+
+        // {id, rot90, rot180, rot270} x {id, transpose}
+        let orientations: Vec<Block> = [b.clone(), b.transpose()]
+            .iter()
+            .flat_map(|block| {
+                vec![
+                    block.clone(),
+                    block.rotate_90(),
+                    block.rotate_90().rotate_90(),
+                    block.rotate_90().rotate_90().rotate_90(),
+                ]
+            })
+            .collect();
+        // the orientations are closed under anti transposition: no orientation is missed
+        for orientation in orientations.iter() {
+            assert!(orientations.contains(&orientation.anti_transpose()));
+        }
+        // this asymmetric block has 8 distinct orientations
+        for (i, orientation) in orientations.iter().enumerate() {
+            assert!(!orientations[i + 1..].contains(orientation));
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(Block::from_str("#####").unwrap().bounding_box(), (1, 5));
+        assert_eq!(
+            Block::from_str("##\n##\n#_").unwrap().bounding_box(),
+            (3, 2)
+        );
+        assert_eq!(
+            Block::from_str("___\n_#_\n_##").unwrap().bounding_box(),
+            (2, 2)
+        );
+        assert_eq!(Block::from_str("___").unwrap().bounding_box(), (0, 0));
+    }
+
+    #[test]
+    fn test_area_bounding_ratio() {
+        assert_eq!(Block::from_str("#####").unwrap().area_bounding_ratio(), 1.0);
+        assert_eq!(
+            Block::from_str("##\n#_").unwrap().area_bounding_ratio(),
+            0.75
+        );
+        // padding does not count
+        assert_eq!(
+            Block::from_str("___\n_##\n_##")
+                .unwrap()
+                .area_bounding_ratio(),
+            1.0
+        );
+        assert_eq!(Block::from_str("_").unwrap().area_bounding_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_padded_to() {
+        let monomino = Block::from_str("#").unwrap();
+        assert_eq!(
+            monomino.padded_to(3, 3).unwrap(),
+            Block::from_str("___\n_#_\n___").unwrap()
+        );
+        let l4 = Block::from_str("#_\n#_\n##").unwrap();
+        let padded = l4.padded_to(5, 5).unwrap();
+        assert_eq!(
+            padded,
+            Block::from_str("_____\n_#___\n_#___\n_##__\n_____").unwrap()
+        );
+        assert_eq!(padded.bounding_box(), l4.bounding_box());
+        // existing padding is dropped, odd margins are rounded toward the top left
+        assert_eq!(
+            Block::from_str("___\n_##\n_##")
+                .unwrap()
+                .padded_to(3, 3)
+                .unwrap(),
+            Block::from_str("##_\n##_\n___").unwrap()
+        );
+        assert_eq!(l4.padded_to(3, 2).unwrap(), l4);
+        assert!(matches!(
+            l4.padded_to(2, 5),
+            Err(BlockError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            Block::from_str("#####").unwrap().padded_to(5, 4),
+            Err(BlockError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_convex_corner_count() {
+        let i5 = Block::from_str("#####").unwrap();
+        assert_eq!(i5.convex_corner_count(), 4);
+        assert_eq!(i5.rotate_90().convex_corner_count(), 4);
+        assert_eq!(Block::from_str("#").unwrap().convex_corner_count(), 4);
+        assert_eq!(Block::from_str("##\n##").unwrap().convex_corner_count(), 4);
+        let x5 = Block::from_str("_#_\n###\n_#_").unwrap();
+        assert_eq!(x5.convex_corner_count(), 8);
+        let w5 = Block::from_str("#__\n##_\n_##").unwrap();
+        assert_eq!(w5.convex_corner_count(), 7);
+        assert_eq!(w5.transpose().convex_corner_count(), 7);
+        assert_eq!(
+            Block::from_str("##_\n_##").unwrap().convex_corner_count(),
+            6
+        );
+        // no block has fewer corners than the straight ones
+        for block in Block::default_block_set() {
+            assert!(block.convex_corner_count() >= i5.convex_corner_count());
+        }
+    }
+
+    #[test]
+    fn test_shape_signature() {
+        let i5 = Block::from_str("#####").unwrap();
+        assert_eq!(i5.shape_signature(), (5, 1, 5));
+        assert_eq!(i5.rotate_90().shape_signature(), (5, 1, 5));
+        assert_eq!(
+            Block::from_str("##\n##").unwrap().shape_signature(),
+            (4, 2, 2)
+        );
+        // padding does not count
+        assert_eq!(
+            Block::from_str("___\n_##\n_#_").unwrap().shape_signature(),
+            (3, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_block_from_rle() {
+        assert_eq!(
+            Block::from_rle("01/01/02").unwrap(),
+            Block::from_str("#_\n#_\n##").unwrap()
+        );
+        // F pentomino
+        assert_eq!(
+            Block::from_rle("12/02/11").unwrap(),
+            Block::from_str("_##\n##_\n_#_").unwrap()
+        );
+        assert_eq!(
+            Block::from_rle("05").unwrap(),
+            Block::from_str("#####").unwrap()
+        );
+        assert!(matches!(
+            Block::from_rle("01/11"),
+            Err(BlockError::Disconnected)
+        ));
+        assert!(matches!(
+            Block::from_rle("2/2"),
+            Err(BlockError::EmptyBlock)
+        ));
+        assert!(matches!(
+            Block::from_rle("0#"),
+            Err(BlockError::InvalidRunLength('#'))
+        ));
+    }
+
+    #[test]
+    fn test_block_overlaps() {
+        let l = Block::from_str("#_\n##").unwrap();
+        let monomino = Block::from_str("#").unwrap();
+        assert!(l.overlaps(&monomino, 1, 1));
+        assert!(!l.overlaps(&monomino, 0, 1));
+        assert!(!l.overlaps(&monomino, -1, 0));
+        assert!(!l.overlaps(&monomino, 2, 2));
+        assert!(l.overlaps(&l, 0, 0));
+        assert!(!l.overlaps(&Block::from_str("_#").unwrap(), 0, 0));
+    }
+
+    #[test]
+    fn test_block_union() {
+        let l = Block::from_str("#_\n##").unwrap();
+        let domino = Block::from_str("##").unwrap();
+        assert_eq!(
+            l.union(&domino, 0, 2),
+            Block::from_str("#_##\n##__").unwrap()
+        );
+        assert_eq!(
+            l.union(&domino, -1, -1),
+            Block::from_str("##_\n_#_\n_##").unwrap()
+        );
+        // filling the gap of the L gives the square
+        assert_eq!(
+            l.union(&Block::from_str("#").unwrap(), 0, 1),
+            Block::from_str("##\n##").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_centroid() {
+        assert_eq!(Block::from_str("#").unwrap().centroid(), (0.0, 0.0));
+        assert_eq!(Block::from_str("###").unwrap().centroid(), (0.0, 1.0));
+        assert_eq!(Block::from_str("##\n##").unwrap().centroid(), (0.5, 0.5));
+        assert_eq!(
+            Block::from_str("#  \n###").unwrap().centroid(),
+            (0.75, 0.75)
+        );
+    }
+
+    #[test]
+    fn test_standard_name() {
+        let names: Vec<Option<PieceName>> = Block::default_block_set()
+            .iter()
+            .map(|b| b.standard_name())
+            .collect();
+        assert!(names.iter().all(|name| name.is_some()));
+        let distinct_names: HashSet<PieceName> = names.iter().flatten().cloned().collect();
+        assert_eq!(distinct_names.len(), Block::default_block_set().len());
+
+        // whatever the orientation
+        let l4 = Block::from_str("#__\n###").unwrap();
+        for orientation in l4.orientations() {
+            assert_eq!(orientation.standard_name(), Some(PieceName::L4));
+        }
+        assert_eq!(
+            Block::from_str("_#_\n###\n_#_").unwrap().standard_name(),
+            Some(PieceName::X5)
+        );
+
+        assert_eq!(Block::from_str("######").unwrap().standard_name(), None);
+        assert_eq!(
+            Block::from_str("##\n##\n#_").unwrap().standard_name(),
+            Some(PieceName::P5)
+        );
+        assert_eq!(Block::from_str("###\n###").unwrap().standard_name(), None);
+
+        assert_eq!(PieceName::I1.long_name(), "monomino");
+        assert_eq!(PieceName::I2.long_name(), "domino");
+        assert_eq!(PieceName::V3.long_name(), "V-tromino");
+        assert_eq!(PieceName::O4.long_name(), "O-tetromino");
+        assert_eq!(PieceName::Y5.long_name(), "Y-pentomino");
+    }
+
+    #[test]
+    fn test_oriented_piece() {
+        let distinct_orientations = |name: PieceName| {
+            let index = PieceName::ALL.iter().position(|n| *n == name).unwrap();
+            OrientedPiece::new(&Block::default_block_set()[index])
+                .distinct
+                .len()
+        };
+        assert_eq!(distinct_orientations(PieceName::I1), 1);
+        assert_eq!(distinct_orientations(PieceName::O4), 1);
+        assert_eq!(distinct_orientations(PieceName::X5), 1);
+        assert_eq!(distinct_orientations(PieceName::I2), 2);
+        assert_eq!(distinct_orientations(PieceName::Z4), 4);
+        assert_eq!(distinct_orientations(PieceName::T4), 4);
+        assert_eq!(distinct_orientations(PieceName::L4), 8);
+        assert_eq!(distinct_orientations(PieceName::F5), 8);
+
+        for block in Block::default_block_set() {
+            let piece = OrientedPiece::new(&block);
+            for index in 0..8 {
+                let (rotation, transposition) = OrientedPiece::rotation_transposition(index);
+                assert_eq!(OrientedPiece::index(rotation, transposition), index);
+                let mut expected = if transposition == 0 {
+                    block.clone()
+                } else {
+                    block.transpose()
+                };
+                for _ in 0..rotation {
+                    expected = expected.rotate_90();
+                }
+                assert_eq!(*piece.get(rotation, transposition), expected);
+                let block_placement = BlockPlacement {
+                    block: block.clone(),
+                    row: 0,
+                    col: 0,
+                    rotation,
+                    transposition,
+                };
+                assert_eq!(block_placement.as_row_col_block().2, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_oriented_piece_next_distinct() {
+        let piece = |name: PieceName| {
+            let index = PieceName::ALL.iter().position(|n| *n == name).unwrap();
+            OrientedPiece::new(&Block::default_block_set()[index])
+        };
+        // all 8 orientations, rotations first then transposed ones
+        let l4 = piece(PieceName::L4);
+        let mut index = 0;
+        let mut cycle = vec![];
+        for _ in 0..8 {
+            index = l4.next_distinct(index);
+            cycle.push(index);
+        }
+        assert_eq!(cycle, vec![1, 2, 3, 4, 5, 6, 7, 0]);
+        // symmetric piece: duplicates are skipped
+        let i2 = piece(PieceName::I2);
+        assert_eq!(i2.next_distinct(0), 1);
+        assert_eq!(i2.next_distinct(1), 0);
+        assert_eq!(i2.distinct_position(2), 0);
+        assert_eq!(i2.next_distinct(2), 1);
+        assert_eq!(i2.next_distinct(4), 0);
+        let o4 = piece(PieceName::O4);
+        assert_eq!(o4.next_distinct(5), 0);
+    }
+
     #[test]
     fn test_board_place() {
         let b = Block::from_str("#  \n###");
@@ -232,10 +1031,10 @@ mod test {
         let b = b.unwrap();
 
         let mut board = Board::new(10, 10);
-        board.place(0, 0, &b, 1);
+        board.place(0, 0, &b, 1).unwrap();
 
         let placement_rule = board.can_place(0, 3, &b.rotate_90().rotate_90(), 1, false);
         println!("{:?}", placement_rule);
-        board.place(0, 3, &b.rotate_90().rotate_90(), 1);
+        board.place(0, 3, &b.rotate_90().rotate_90(), 1).unwrap();
     }
 }
@@ -1,17 +1,138 @@
-use crate::block::Block;
+use crate::block::{Block, OrientedPiece};
+use crate::board::BoardError::{DimensionMismatch, InvalidCell};
+use crate::strategy::{evaluate, BlockPlacement, Player, ScoringConfig};
+use crate::theme::Theme;
 use nalgebra::DMatrix;
+use std::collections::HashSet;
+use std::fmt;
+use thiserror::Error;
 
 type CellType = u8;
 const FREE_CELL: CellType = 0;
 
+/// Zobrist key of a cell type at a board position, see [`Board::zobrist_hash`].
+fn zobrist_key(row: usize, col: usize, cell_type: CellType) -> u64 {
+    // splitmix64 finalizer over the (row, col, cell type) triple
+    let mut z = ((row as u64) << 40 | (col as u64) << 16 | cell_type as u64)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// How board edges connect when looking for neighbor cells.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum Topology {
+    #[default]
+    Grid,
+    /// opposite edges are adjacent
+    Torus,
+}
+
+/// Rule variants checked by [`Board::can_place`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct PlacementRuleset {
+    /// the first block of each player must cover a free board corner,
+    /// otherwise it can be placed anywhere it does not overlap
+    pub first_must_cover_start: bool,
+    /// blocks cannot touch any other block by a side, not only the player own blocks,
+    /// diagonal contacts are still allowed
+    pub forbid_any_side_contact: bool,
+}
+
+impl Default for PlacementRuleset {
+    fn default() -> Self {
+        PlacementRuleset {
+            first_must_cover_start: true,
+            forbid_any_side_contact: false,
+        }
+    }
+}
+
+/// Callback given the cells changed by [`Board::place`] and [`Board::undo`].
+type OnChange = Box<dyn FnMut(&[(usize, usize)]) + Send>;
+
+/// Optional [`OnChange`] callback of a board.
+///
+/// Not part of the board state: clones are not observed and boards compare equal
+/// whatever their observers.
+#[derive(Default)]
+struct ChangeObserver(Option<OnChange>);
+
+impl ChangeObserver {
+    fn notify(&mut self, cells: &[(usize, usize)]) {
+        if let Some(on_change) = self.0.as_mut() {
+            on_change(cells);
+        }
+    }
+}
+
+impl Clone for ChangeObserver {
+    fn clone(&self) -> Self {
+        ChangeObserver(None)
+    }
+}
+
+impl PartialEq for ChangeObserver {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for ChangeObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(_) => write!(f, "ChangeObserver(Some(..))"),
+            None => write!(f, "ChangeObserver(None)"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub(crate) struct Board {
     data: DMatrix<CellType>,
+    topology: Topology,
+    ruleset: PlacementRuleset,
+    on_change: ChangeObserver,
 }
 
-#[derive(Debug)]
+#[derive(Error, Debug)]
+pub(crate) enum BoardError {
+    #[error("Dimension mismatch")]
+    DimensionMismatch,
+    #[error("Invalid cell {0:?}")]
+    InvalidCell(char),
+    #[cfg(any(test, feature = "net"))]
+    #[error("Invalid header {0:?}, expected e.g. \"20x20:\"")]
+    InvalidHeader(String),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub(crate) enum MoveError {
+    #[error("game is over")]
+    GameOver,
+    #[error("not player {0} turn")]
+    NotYourTurn(u8),
+    #[cfg(any(test, feature = "net"))]
+    #[error("no player {0} in the game")]
+    UnknownPlayer(u8),
+    #[error("player {0} is already eliminated")]
+    AlreadyEliminated(u8),
+    #[error("illegal block placement")]
+    IllegalPlacement,
+    /// the piece was already played, or never was in the player hand
+    #[error("piece not in player {0} hand")]
+    PieceNotInHand(u8),
+    #[error("block overlaps non-free cells")]
+    Overlapping,
+    #[error("block is out of the board")]
+    OutOfBounds,
+}
+
+#[derive(Debug, PartialEq)]
 pub(crate) struct PlacementRule {
     overlapping: Option<bool>,
+    /// any block with [`PlacementRuleset::forbid_any_side_contact`]
     own_block_touching_sides: Option<bool>,
     no_corner: Option<bool>,
 }
@@ -30,11 +151,13 @@ impl PlacementRule {
 }
 
 pub(crate) struct BruteForceSearchPlace {
-    block: Block,
+    piece: OrientedPiece,
     block_type: CellType,
     first_block: bool,
     board: Board,
     start: usize,
+    /// stop after examining this many positions, unbounded if `None`
+    max_positions: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -49,24 +172,18 @@ impl Iterator for BruteForceSearchPlace {
     type Item = BlockPosition;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let iterations = 2usize * 4usize * self.board.nrows() * self.board.ncols();
+        // only distinct orientations, symmetric blocks would yield duplicated placements
+        let cells = self.board.nrows() * self.board.ncols();
+        let iterations = self.piece.distinct.len() * cells;
+        let iterations = self
+            .max_positions
+            .map_or(iterations, |max| iterations.min(max));
         for i in self.start..iterations {
             let col: usize = i % self.board.ncols();
             let row: usize = (i / self.board.ncols()) % self.board.nrows();
-            let rotation: u8 = ((i / (self.board.ncols() * self.board.nrows())) % 4) as u8;
-            let transposition: u8 = ((i / (self.board.ncols() * self.board.nrows() * 4)) % 2) as u8;
-
-            let block = if transposition == 0 {
-                &self.block
-            } else {
-                &self.block.transpose()
-            };
-            let block = match rotation {
-                1 => &block.rotate_90(),
-                2 => &block.rotate_90().rotate_90(),
-                3 => &block.rotate_90().rotate_90().rotate_90(),
-                _ => block,
-            };
+            let orientation = self.piece.distinct[i / cells];
+            let (rotation, transposition) = OrientedPiece::rotation_transposition(orientation);
+            let block = self.piece.get(rotation, transposition);
             let placement_rule =
                 self.board
                     .can_place(row, col, block, self.block_type, self.first_block);
@@ -85,11 +202,139 @@ impl Iterator for BruteForceSearchPlace {
     }
 }
 
+/// One line per row, `.` for a free cell and the player id otherwise, parsable by [`Board::from_str`].
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.nrows() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for col in 0..self.ncols() {
+                match self.data[(row, col)] {
+                    FREE_CELL => write!(f, ".")?,
+                    cell_type => write!(f, "{}", cell_type)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Board {
     pub fn new(nrows: usize, ncols: usize) -> Self {
         Board {
             data: DMatrix::from_element(nrows, ncols, FREE_CELL),
+            topology: Topology::Grid,
+            ruleset: PlacementRuleset::default(),
+            on_change: ChangeObserver::default(),
+        }
+    }
+
+    /// Parse a board, one line per row, `.` for a free cell and a digit for a player cell.
+    pub fn from_str(s: &str) -> Result<Self, BoardError> {
+        let rows: Vec<Vec<CellType>> = s
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        '.' => Ok(FREE_CELL),
+                        '1'..='9' => Ok(c as CellType - b'0'),
+                        _ => Err(InvalidCell(c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let ncols = rows.first().map(|r| r.len()).unwrap_or(0);
+        if ncols == 0 || rows.iter().any(|r| r.len() != ncols) {
+            return Err(DimensionMismatch);
+        }
+
+        let rows_flattened: Vec<CellType> = rows.iter().flatten().cloned().collect();
+
+        Ok(Board {
+            data: DMatrix::from_row_iterator(rows.len(), ncols, rows_flattened),
+            topology: Topology::Grid,
+            ruleset: PlacementRuleset::default(),
+            on_change: ChangeObserver::default(),
+        })
+    }
+
+    /// Board with `placements` played in order, each checked against the game rules.
+    ///
+    /// The first placement of each player is played as its first block.
+    #[cfg(test)]
+    pub fn from_placements(
+        nrows: usize,
+        ncols: usize,
+        placements: &[(CellType, BlockPlacement)],
+    ) -> Result<Self, MoveError> {
+        let mut board = Board::new(nrows, ncols);
+        let mut players_started = HashSet::new();
+        for (player_id, placement) in placements {
+            let first_block = players_started.insert(*player_id);
+            if !board
+                .can_place_placement(placement, *player_id, first_block)
+                .placement_ok()
+            {
+                return Err(MoveError::IllegalPlacement);
+            }
+            board.apply(placement, *player_id)?;
+        }
+        Ok(board)
+    }
+
+    /// Single line code `<nrows>x<ncols>:<cells>`, one digit per cell in row-major order
+    /// and `0` for a free cell, e.g. `2x3:100002`.
+    #[cfg(any(test, feature = "net"))]
+    pub fn to_compact_string(&self) -> String {
+        let cells: String = self
+            .cells()
+            .map(|(_, _, cell_type)| cell_type.to_string())
+            .collect();
+        format!("{}x{}:{}", self.nrows(), self.ncols(), cells)
+    }
+
+    /// Parse a code from [`Board::to_compact_string`].
+    #[cfg(any(test, feature = "net"))]
+    pub fn from_compact_string(s: &str) -> Result<Self, BoardError> {
+        let invalid_header =
+            || BoardError::InvalidHeader(s.chars().take_while(|&c| c != ':').collect());
+        let (header, cells) = s.trim().split_once(':').ok_or_else(invalid_header)?;
+        let (nrows, ncols) = header.split_once('x').ok_or_else(invalid_header)?;
+        let nrows: usize = nrows.parse().map_err(|_| invalid_header())?;
+        let ncols: usize = ncols.parse().map_err(|_| invalid_header())?;
+        let cells: Vec<CellType> = cells
+            .chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) => Ok(digit as CellType),
+                None => Err(InvalidCell(c)),
+            })
+            .collect::<Result<_, _>>()?;
+        if nrows == 0 || ncols == 0 || Some(cells.len()) != nrows.checked_mul(ncols) {
+            return Err(DimensionMismatch);
         }
+        Ok(Board {
+            data: DMatrix::from_row_iterator(nrows, ncols, cells),
+            topology: Topology::Grid,
+            ruleset: PlacementRuleset::default(),
+            on_change: ChangeObserver::default(),
+        })
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Call `on_change` with the cells changed by each [`Board::place`] and [`Board::undo`],
+    /// e.g. for incremental rendering. Replaces the previous callback if any.
+    #[cfg(test)]
+    pub fn set_on_change(&mut self, on_change: OnChange) {
+        self.on_change = ChangeObserver(Some(on_change));
+    }
+
+    pub fn set_ruleset(&mut self, ruleset: PlacementRuleset) {
+        self.ruleset = ruleset;
     }
 
     pub fn nrows(&self) -> usize {
@@ -99,18 +344,318 @@ impl Board {
     pub fn ncols(&self) -> usize {
         self.data.ncols()
     }
-    pub fn free_at_row_col(&self, row: usize, col: usize) -> bool {
-        if row < self.data.nrows() && col < self.data.ncols() {
-            self.data[(row, col)] == FREE_CELL
+    pub fn count_cells(&self, block_type: CellType) -> usize {
+        self.data.iter().filter(|&&cell| cell == block_type).count()
+    }
+
+    /// Board with row and column indexes along the left and top edges, e.g. to follow a
+    /// move log.
+    ///
+    /// Unlike [`Board`] `Display`, the output cannot be parsed back.
+    pub fn print_with_coordinates(&self) -> String {
+        let gutter = self.nrows().saturating_sub(1).to_string().len();
+        let mut s = " ".repeat(gutter);
+        for col in 0..self.ncols() {
+            s += &format!("{:>3}", col);
+        }
+        for row in 0..self.nrows() {
+            s += &format!("\n{:>gutter$}", row);
+            for col in 0..self.ncols() {
+                match self.data[(row, col)] {
+                    FREE_CELL => s += "  .",
+                    cell_type => s += &format!("{:>3}", cell_type),
+                }
+            }
+        }
+        s
+    }
+
+    /// Free cells where `player_id` could start a new block: touching one of its blocks
+    /// by a corner only, or a free start corner for the first block.
+    pub fn open_corners(&self, player_id: CellType, first_block: bool) -> Vec<(usize, usize)> {
+        let monomino = Block::from_str("#").expect("valid block");
+        (0..self.nrows())
+            .flat_map(|row| (0..self.ncols()).map(move |col| (row, col)))
+            .filter(|&(row, col)| {
+                self.can_place(row, col, &monomino, player_id, first_block)
+                    .placement_ok()
+            })
+            .collect()
+    }
+
+    /// Number of [`Board::open_corners`] of `player_id`, the start corners until it placed a block.
+    pub fn open_corner_count(&self, player_id: CellType) -> usize {
+        let first_block = self.count_cells(player_id) == 0;
+        self.open_corners(player_id, first_block).len()
+    }
+
+    /// Plain text summary of the board for screen readers: size, cells of each player
+    /// and the open corners of `player_id`.
+    pub fn describe(&self, player_id: CellType, first_block: bool) -> String {
+        let mut player_ids: Vec<CellType> = self
+            .data
+            .iter()
+            .copied()
+            .filter(|&cell_type| cell_type != FREE_CELL)
+            .chain([player_id])
+            .collect();
+        player_ids.sort_unstable();
+        player_ids.dedup();
+        let cells: Vec<String> = player_ids
+            .iter()
+            .map(|&id| format!("player {} has {} cells", id, self.count_cells(id)))
+            .collect();
+        let open_corners = self.open_corners(player_id, first_block);
+        let corners = if open_corners.is_empty() {
+            String::from("none")
         } else {
-            false
+            open_corners
+                .iter()
+                .map(|(row, col)| format!("row {} column {}", row, col))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        format!(
+            "{} by {} board, {} free cells. {}. Open corners for player {}: {}.",
+            self.nrows(),
+            self.ncols(),
+            self.free_cells(),
+            cells.join(", "),
+            player_id,
+            corners
+        )
+    }
+
+    /// SVG image of the board with one `cell_px` wide square per non-free cell.
+    pub fn to_svg(&self, cell_px: usize, theme: &Theme) -> String {
+        let (width, height) = (self.ncols() * cell_px, self.nrows() * cell_px);
+        let (red, green, blue) = theme.background;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" style=\"background-color:#{red:02x}{green:02x}{blue:02x}\">\n"
+        );
+        for (row, col, cell_type) in self.cells() {
+            if cell_type == FREE_CELL {
+                continue;
+            }
+            let (red, green, blue) = theme.player_rgb(cell_type);
+            svg += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{cell_px}\" height=\"{cell_px}\" \
+                 fill=\"#{red:02x}{green:02x}{blue:02x}\"/>\n",
+                col * cell_px,
+                row * cell_px,
+            );
         }
+        svg += "</svg>\n";
+        svg
     }
-    pub fn at_row_col(&self, row: usize, col: usize) -> CellType {
-        if row < self.data.nrows() && col < self.data.ncols() {
-            self.data[(row, col)]
+
+    /// Whether relabeling the player ids of `other` with some bijection gives this board.
+    #[cfg(test)]
+    pub fn equal_up_to_player_permutation(&self, other: &Board) -> bool {
+        if self.data.shape() != other.data.shape() {
+            return false;
+        }
+        // the relabeling is forced by the first cell of each player
+        let mut relabel = std::collections::HashMap::new();
+        let mut used = HashSet::new();
+        for (&cell_type, &other_cell_type) in self.data.iter().zip(other.data.iter()) {
+            if (cell_type == FREE_CELL) != (other_cell_type == FREE_CELL) {
+                return false;
+            }
+            if cell_type == FREE_CELL {
+                continue;
+            }
+            match relabel.get(&other_cell_type) {
+                Some(&relabeled) if relabeled != cell_type => return false,
+                Some(_) => {}
+                None => {
+                    if !used.insert(cell_type) {
+                        return false; // two players of other would map to the same player
+                    }
+                    relabel.insert(other_cell_type, cell_type);
+                }
+            }
+        }
+        true
+    }
+
+    /// Score of `player` if the game ended now on this board, including the
+    /// [`ScoringConfig::contiguity_bonus`] for its largest diagonal
+    /// [`Board::connected_components_of`].
+    ///
+    /// With the standard rules, where each block touches an earlier one by a corner, the
+    /// largest component grows with every placed block, pre-seeded cells of a puzzle may
+    /// stay apart.
+    pub fn score_estimate(&self, player: &Player, scoring: &ScoringConfig) -> i32 {
+        let largest_component = if scoring.contiguity_bonus == 0 {
+            0
         } else {
-            FREE_CELL
+            self.connected_components_of(player.player_id, true)
+                .iter()
+                .map(Vec::len)
+                .max()
+                .unwrap_or(0)
+        };
+        player.score_with(scoring) + scoring.contiguity_bonus * largest_component as i32
+    }
+
+    pub fn free_cells(&self) -> usize {
+        self.data.iter().filter(|&&cell| cell == FREE_CELL).count()
+    }
+
+    /// Ratio of non-free cells, between 0.0 (empty board) and 1.0 (full board).
+    pub fn fill_ratio(&self) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        1.0 - (self.free_cells() as f32 / self.data.len() as f32)
+    }
+
+    /// 4-connected regions of free cells.
+    pub fn empty_regions(&self) -> Vec<HashSet<(usize, usize)>> {
+        self.regions_of(FREE_CELL, false)
+    }
+
+    /// Biggest 4-connected region of free cells, `None` on a full board.
+    pub fn largest_empty_region(&self) -> Option<HashSet<(usize, usize)>> {
+        self.empty_regions().into_iter().reduce(|largest, region| {
+            // the first region wins ties
+            if region.len() > largest.len() {
+                region
+            } else {
+                largest
+            }
+        })
+    }
+
+    /// Components of `player_id` cells connected by a side, or by a side or a corner with
+    /// `diagonals`, in row-major order.
+    ///
+    /// Blocks of a player never touch by a side, so each side-connected component is a
+    /// placed block, while blocks chained corner to corner share a diagonal component.
+    pub fn connected_components_of(
+        &self,
+        player_id: CellType,
+        diagonals: bool,
+    ) -> Vec<Vec<(usize, usize)>> {
+        self.regions_of(player_id, diagonals)
+            .into_iter()
+            .map(|region| {
+                let mut cells: Vec<(usize, usize)> = region.into_iter().collect();
+                cells.sort_unstable();
+                cells
+            })
+            .collect()
+    }
+
+    /// Free cells `player_id` could still claim: flood fill from its open corners
+    /// through free cells not side-adjacent to its own cells.
+    ///
+    /// An upper bound of the territory left to the player, ignoring its remaining pieces.
+    pub fn reachable_free_cells(&self, player_id: CellType) -> usize {
+        let sides = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+        let claimable = |row: usize, col: usize| {
+            self.data[(row, col)] == FREE_CELL
+                && !sides.iter().any(|&(drow, dcol)| {
+                    self.neighbor(row, col, drow, dcol)
+                        .is_some_and(|(r, c)| self.data[(r, c)] == player_id)
+                })
+        };
+        let first_block = self.count_cells(player_id) == 0;
+        let mut stack = self.open_corners(player_id, first_block);
+        let mut visited: HashSet<(usize, usize)> = stack.iter().copied().collect();
+        while let Some((row, col)) = stack.pop() {
+            for (drow, dcol) in sides {
+                if let Some((r, c)) = self.neighbor(row, col, drow, dcol) {
+                    if claimable(r, c) && visited.insert((r, c)) {
+                        stack.push((r, c));
+                    }
+                }
+            }
+        }
+        visited.len()
+    }
+
+    /// 4-connected regions of `cell_type` cells, 8-connected with `diagonals`, ordered by
+    /// their first cell.
+    fn regions_of(&self, cell_type: CellType, diagonals: bool) -> Vec<HashSet<(usize, usize)>> {
+        let is_cell_type = |row: usize, col: usize| self.get(row, col) == Some(cell_type);
+        let mut visited = HashSet::new();
+        let mut regions = vec![];
+        for row in 0..self.nrows() {
+            for col in 0..self.ncols() {
+                if !is_cell_type(row, col) || visited.contains(&(row, col)) {
+                    continue;
+                }
+                let mut region = HashSet::new();
+                let mut stack = vec![(row, col)];
+                visited.insert((row, col));
+                while let Some((r, c)) = stack.pop() {
+                    region.insert((r, c));
+                    let sides = [
+                        (r.wrapping_sub(1), c),
+                        (r + 1, c),
+                        (r, c.wrapping_sub(1)),
+                        (r, c + 1),
+                    ];
+                    let corners = [
+                        (r.wrapping_sub(1), c.wrapping_sub(1)),
+                        (r.wrapping_sub(1), c + 1),
+                        (r + 1, c.wrapping_sub(1)),
+                        (r + 1, c + 1),
+                    ];
+                    let corners = if diagonals { &corners[..] } else { &[] };
+                    for &(nr, nc) in sides.iter().chain(corners) {
+                        // out of bounds (including wrapped) cells are ignored
+                        if is_cell_type(nr, nc) && visited.insert((nr, nc)) {
+                            stack.push((nr, nc));
+                        }
+                    }
+                }
+                regions.push(region);
+            }
+        }
+        regions
+    }
+
+    /// Every cell as `(row, col, cell type)`, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, CellType)> + '_ {
+        (0..self.nrows()).flat_map(move |row| {
+            (0..self.ncols()).map(move |col| (row, col, self.data[(row, col)]))
+        })
+    }
+
+    /// Cell at `(row, col)`, `None` out of the board.
+    pub fn get(&self, row: usize, col: usize) -> Option<CellType> {
+        self.data.get((row, col)).copied()
+    }
+
+    pub fn free_at_row_col(&self, row: usize, col: usize) -> bool {
+        self.get(row, col) == Some(FREE_CELL)
+    }
+    pub fn at_row_col(&self, row: usize, col: usize) -> CellType {
+        self.get(row, col).unwrap_or(FREE_CELL)
+    }
+
+    /// Position of the cell at `row + drow`, `col + dcol`, if any with the board topology.
+    fn neighbor(&self, row: usize, col: usize, drow: i32, dcol: i32) -> Option<(usize, usize)> {
+        let neighbor_row = row as i32 + drow;
+        let neighbor_col = col as i32 + dcol;
+        let nrows = self.data.nrows() as i32;
+        let ncols = self.data.ncols() as i32;
+        match self.topology {
+            Topology::Grid => {
+                if (0..nrows).contains(&neighbor_row) && (0..ncols).contains(&neighbor_col) {
+                    Some((neighbor_row as usize, neighbor_col as usize))
+                } else {
+                    None
+                }
+            }
+            Topology::Torus => Some((
+                neighbor_row.rem_euclid(nrows) as usize,
+                neighbor_col.rem_euclid(ncols) as usize,
+            )),
         }
     }
 
@@ -145,26 +690,25 @@ impl Board {
         }
         placement_rule.overlapping = Some(false);
 
-        // check block neighbor cells are not the same cell type as the block
+        // check block neighbor cells are not the same cell type as the block,
+        // nor any player cell with `forbid_any_side_contact`
         for block_row in 0..block.nrows() {
             for block_col in 0..block.ncols() {
                 let block_cell = block.cell_at_row_col(block_row, block_col);
                 if block_cell {
                     for (drow, dcol) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-                        let board_row = row + block_row;
-                        let board_col = col + block_col;
-                        if (board_row as i32 + drow >= 0)
-                            && (board_col as i32 + dcol >= 0)
-                            && (board_row as i32 + drow < self.data.nrows() as i32)
-                            && (board_col as i32 + dcol < self.data.ncols() as i32)
-                        {
-                            let board_row = ((board_row as i32) + drow) as usize;
-                            let board_col = ((board_col as i32) + dcol) as usize;
-                            let board_cell = self.data[(board_row, board_col)];
-                            if board_cell == block_type {
-                                placement_rule.own_block_touching_sides = Some(true);
-                                break;
+                        let board_cell = self
+                            .neighbor(row + block_row, col + block_col, drow, dcol)
+                            .and_then(|(board_row, board_col)| self.get(board_row, board_col));
+                        let touching = match board_cell {
+                            Some(FREE_CELL) | None => false,
+                            Some(cell_type) => {
+                                cell_type == block_type || self.ruleset.forbid_any_side_contact
                             }
+                        };
+                        if touching {
+                            placement_rule.own_block_touching_sides = Some(true);
+                            break;
                         }
                     }
                 }
@@ -175,28 +719,14 @@ impl Board {
         }
         placement_rule.own_block_touching_sides = Some(false);
 
-        if first_block {
-            // check block fills a corner and the corner is empty
-            for (corner_row, corner_col) in [
-                (0, 0),
-                (0, self.data.ncols() - 1),
-                (self.data.nrows() - 1, 0),
-                (self.data.nrows() - 1, self.data.ncols() - 1),
-            ] {
-                if !self.free_at_row_col(corner_row, corner_col) {
-                    continue; // corner already taken
-                }
-                let relative_row = corner_row as i32 - row as i32;
-                let relative_col = corner_col as i32 - col as i32;
-                if (relative_row >= 0)
-                    && (relative_col >= 0)
-                    && (relative_row < block.nrows() as i32)
-                    && (relative_col < block.ncols() as i32)
-                {
-                    let block_row = relative_row as usize;
-                    let block_col = relative_col as usize;
+        if first_block && !self.ruleset.first_must_cover_start {
+            placement_rule.no_corner = Some(false);
+        } else if first_block {
+            // check block covers a corner, free since the block does not overlap
+            for block_row in 0..block.nrows() {
+                for block_col in 0..block.ncols() {
                     let block_cell = block.cell_at_row_col(block_row, block_col);
-                    if block_cell {
+                    if block_cell && self.is_corner(row + block_row, col + block_col) {
                         placement_rule.no_corner = Some(false);
                         break;
                     }
@@ -211,24 +741,15 @@ impl Board {
             for block_row in 0..block.nrows() {
                 for block_col in 0..block.ncols() {
                     let block_cell = block.cell_at_row_col(block_row, block_col);
-                    if block_cell {
-                        for (drow, dcol) in [(-1, -1), (1, 1), (1, -1), (-1, 1)] {
-                            let board_row = row + block_row;
-                            let board_col = col + block_col;
-                            if (board_row as i32 + drow >= 0)
-                                && (board_col as i32 + dcol >= 0)
-                                && (board_row as i32 + drow < self.data.nrows() as i32)
-                                && (board_col as i32 + dcol < self.data.ncols() as i32)
-                            {
-                                let board_row = ((board_row as i32) + drow) as usize;
-                                let board_col = ((board_col as i32) + dcol) as usize;
-                                let board_cell = self.data[(board_row, board_col)];
-                                if board_cell == block_type {
-                                    placement_rule.no_corner = Some(false);
-                                    break;
-                                }
-                            }
-                        }
+                    if block_cell
+                        && self.has_diagonal_same_color(
+                            row + block_row,
+                            col + block_col,
+                            block_type,
+                        )
+                    {
+                        placement_rule.no_corner = Some(false);
+                        break;
                     }
                 }
             }
@@ -241,31 +762,1342 @@ impl Board {
         placement_rule
     }
 
-    pub fn place(&mut self, row: usize, col: usize, block: &Block, block_type: CellType) {
+    /// Start cells covered by the first block of each player: the four board corners,
+    /// top left, top right, bottom left then bottom right.
+    ///
+    /// `None` for an empty board, e.g. [`Board::default`].
+    pub fn corners(&self) -> Option<[(usize, usize); 4]> {
+        let last_row = self.nrows().checked_sub(1)?;
+        let last_col = self.ncols().checked_sub(1)?;
+        Some([(0, 0), (0, last_col), (last_row, 0), (last_row, last_col)])
+    }
+
+    /// Whether `(row, col)` is one of the [`Board::corners`].
+    pub fn is_corner(&self, row: usize, col: usize) -> bool {
+        self.corners()
+            .is_some_and(|corners| corners.contains(&(row, col)))
+    }
+
+    /// Whether a diagonal neighbor of the cell at `board_row`, `board_col` is of `block_type`,
+    /// the corner contact a block needs after the first one.
+    pub fn has_diagonal_same_color(
+        &self,
+        board_row: usize,
+        board_col: usize,
+        block_type: CellType,
+    ) -> bool {
+        [(-1, -1), (1, 1), (1, -1), (-1, 1)]
+            .into_iter()
+            .filter_map(|(drow, dcol)| self.neighbor(board_row, board_col, drow, dcol))
+            .any(|(row, col)| self.get(row, col) == Some(block_type))
+    }
+
+    /// Same as [`Board::can_place`] for the oriented block of `placement`.
+    pub fn can_place_placement(
+        &self,
+        placement: &BlockPlacement,
+        block_type: CellType,
+        first_block: bool,
+    ) -> PlacementRule {
+        let (row, col, block) = placement.as_row_col_block();
+        self.can_place(row, col, &block, block_type, first_block)
+    }
+
+    /// Write `block` cells on the board with `(row, col)` as top left corner.
+    ///
+    /// Only checks the block fits on free cells, see [`Board::can_place`] for the game rules.
+    /// The board is left unchanged on error.
+    /// Returns the board cells now occupied by the block.
+    pub fn place(
+        &mut self,
+        row: usize,
+        col: usize,
+        block: &Block,
+        block_type: CellType,
+    ) -> Result<Vec<(usize, usize)>, MoveError> {
+        let mut occupied_cells = vec![];
         for block_row in 0..block.nrows() {
             for block_col in 0..block.ncols() {
-                let board_row = row + block_row;
-                let board_col = col + block_col;
-                let block_cell = block.cell_at_row_col(block_row, block_col);
-                if block_cell {
-                    self.data[(board_row, board_col)] = block_type;
+                if block.cell_at_row_col(block_row, block_col) {
+                    let board_row = row + block_row;
+                    let board_col = col + block_col;
+                    if board_row >= self.nrows() || board_col >= self.ncols() {
+                        return Err(MoveError::OutOfBounds);
+                    }
+                    if !self.free_at_row_col(board_row, board_col) {
+                        return Err(MoveError::Overlapping);
+                    }
+                    occupied_cells.push((board_row, board_col));
                 }
             }
         }
+        for &(board_row, board_col) in &occupied_cells {
+            self.data[(board_row, board_col)] = block_type;
+        }
+        self.on_change.notify(&occupied_cells);
+        Ok(occupied_cells)
+    }
+
+    /// Place the oriented block of `placement` on the board, see [`Board::place`].
+    pub fn apply(
+        &mut self,
+        placement: &BlockPlacement,
+        block_type: CellType,
+    ) -> Result<Vec<(usize, usize)>, MoveError> {
+        let (row, col, block) = placement.as_row_col_block();
+        self.place(row, col, &block, block_type)
+    }
+
+    /// [`Board::apply`] then evaluate the resulting board for `player_id` against `players`,
+    /// for simulation loops.
+    ///
+    /// The board is left mutated, undo with the cells of [`BlockPlacement::occupied_cells`].
+    pub fn apply_and_score(
+        &mut self,
+        placement: &BlockPlacement,
+        player_id: u8,
+        players: &[Player],
+    ) -> Result<i32, MoveError> {
+        self.apply(placement, player_id)?;
+        Ok(evaluate(self, player_id, players))
+    }
+
+    /// Free the given cells, e.g. the cells returned by [`Board::apply`].
+    pub fn undo(&mut self, occupied_cells: &[(usize, usize)]) {
+        for &(row, col) in occupied_cells {
+            self.data[(row, col)] = FREE_CELL;
+        }
+        self.on_change.notify(occupied_cells);
+    }
+
+    /// Board rotated a quarter turn clockwise, with the same topology and ruleset.
+    #[cfg(test)]
+    pub fn rotate_90(&self) -> Board {
+        let nrows = self.nrows();
+        Board {
+            data: DMatrix::from_fn(self.ncols(), nrows, |row, col| {
+                self.data[(nrows - 1 - col, row)]
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// Board mirrored left to right, with the same topology and ruleset.
+    #[cfg(test)]
+    pub fn mirror(&self) -> Board {
+        let ncols = self.ncols();
+        Board {
+            data: DMatrix::from_fn(self.nrows(), ncols, |row, col| {
+                self.data[(row, ncols - 1 - col)]
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// Smallest of the 8 rotations and reflections of the board, comparing dimensions then
+    /// cells in row-major order: symmetric boards share the same canonical board.
+    ///
+    /// Only meaningful for values invariant under board symmetries, such as
+    /// [`evaluate`] with the four corners as start cells. Cell coordinates of the
+    /// canonical board are not those of this board.
+    #[cfg(test)]
+    pub fn canonical(&self) -> Board {
+        let rotations = |board: Board| {
+            std::iter::successors(Some(board), |board| Some(board.rotate_90())).take(4)
+        };
+        rotations(self.clone())
+            .chain(rotations(self.mirror()))
+            .min_by_key(|board| {
+                let cells: Vec<CellType> = board.cells().map(|(_, _, cell)| cell).collect();
+                (board.nrows(), board.ncols(), cells)
+            })
+            .expect("8 symmetric boards")
+    }
+
+    /// Zobrist hash of the board, equal boards have equal hashes.
+    ///
+    /// Tree search strategies key their transposition tables by
+    /// [`Board::symmetric_zobrist_hash`] instead.
+    #[cfg(test)]
+    pub fn zobrist_hash(&self) -> u64 {
+        self.cells()
+            .filter(|&(_, _, cell_type)| cell_type != FREE_CELL)
+            .fold(0, |hash, (row, col, cell_type)| {
+                hash ^ zobrist_key(row, col, cell_type)
+            })
+    }
+
+    /// Smallest Zobrist hash of the 8 rotations and reflections of the board, computed
+    /// without building them: symmetric boards share the same hash.
+    ///
+    /// Quarter turns and transpositions swap the board dimensions, so a non square board
+    /// only uses its 4 images with the same dimensions: identity, half turn and the two
+    /// mirrors.
+    ///
+    /// Meant for transposition tables in tree search strategies.
+    ///
+    /// Only meaningful for values invariant under board symmetries, such as [`evaluate`]
+    /// with the four corners as start cells.
+    pub fn symmetric_zobrist_hash(&self) -> u64 {
+        let mut hashes = [0u64; 8];
+        for (row, col, cell_type) in self.cells().filter(|&(_, _, cell)| cell != FREE_CELL) {
+            let flipped_row = self.nrows() - 1 - row;
+            let flipped_col = self.ncols() - 1 - col;
+            // the 4 images keeping the dimensions (identity, half turn, mirrors),
+            // then the 4 images swapping them (quarter turns, transpositions)
+            let images = [
+                (row, col),
+                (flipped_row, flipped_col),
+                (row, flipped_col),
+                (flipped_row, col),
+                (col, flipped_row),
+                (flipped_col, row),
+                (flipped_col, flipped_row),
+                (col, row),
+            ];
+            for (hash, (image_row, image_col)) in hashes.iter_mut().zip(images) {
+                *hash ^= zobrist_key(image_row, image_col, cell_type);
+            }
+        }
+        let symmetries = if self.nrows() == self.ncols() { 8 } else { 4 };
+        hashes[..symmetries]
+            .iter()
+            .copied()
+            .min()
+            .unwrap_or_default()
     }
 
+    /// Iterate over the legal positions of `block`.
+    ///
+    /// The search stops after examining `max_positions` positions if any,
+    /// trading completeness for speed on huge boards.
     pub fn bruteforce_search_place(
         &self,
         block: &Block,
         block_type: CellType,
         first_block: bool,
+        max_positions: Option<usize>,
     ) -> BruteForceSearchPlace {
         BruteForceSearchPlace {
-            block: block.clone(),
+            piece: OrientedPiece::new(block),
             block_type,
             first_block,
             board: self.clone(),
             start: 0,
+            max_positions,
+        }
+    }
+
+    /// All legal positions (anchor and orientation) of `block` on the board.
+    pub fn legal_placements(
+        &self,
+        block: &Block,
+        block_type: CellType,
+        first_block: bool,
+    ) -> Vec<BlockPosition> {
+        self.bruteforce_search_place(block, block_type, first_block, None)
+            .collect()
+    }
+
+    /// Whether each of the player blocks can be placed somewhere on the board.
+    pub fn placeable_pieces(&self, player: &Player, first_block: bool) -> Vec<bool> {
+        let fits_free_region = self.free_region_filter();
+        player
+            .blocks
+            .iter()
+            .map(|block| {
+                fits_free_region(block)
+                    && self
+                        .bruteforce_search_place(block, player.player_id, first_block, None)
+                        .next()
+                        .is_some()
+            })
+            .collect()
+    }
+
+    /// Cheap check that a block may fit in one of the free regions, comparing its
+    /// [`Block::shape_signature`] with the cells and extent of the largest regions.
+    ///
+    /// Always true on a torus, where regions wrap around the edges.
+    fn free_region_filter(&self) -> impl Fn(&Block) -> bool {
+        let (max_cells, max_extent) = match self.topology {
+            Topology::Grid => self
+                .empty_regions()
+                .iter()
+                .map(|region| {
+                    let extent = |coordinate: fn(&(usize, usize)) -> usize| {
+                        let min = region.iter().map(coordinate).min().unwrap_or(0);
+                        let max = region.iter().map(coordinate).max().unwrap_or(0);
+                        max - min + 1
+                    };
+                    (
+                        region.len(),
+                        extent(|cell| cell.0).max(extent(|cell| cell.1)),
+                    )
+                })
+                .fold((0, 0), |(cells, extent), (region_cells, region_extent)| {
+                    (cells.max(region_cells), extent.max(region_extent))
+                }),
+            Topology::Torus => (usize::MAX, usize::MAX),
+        };
+        move |block| {
+            let (cells, _, max_dimension) = block.shape_signature();
+            cells <= max_cells && max_dimension <= max_extent
+        }
+    }
+
+    /// Whether `victim` has no legal move left once `placer_id` plays `placement`,
+    /// e.g. to warn a player their move eliminates an opponent.
+    ///
+    /// The placement is applied to a copy of the board, a placement that does not fit
+    /// leaves no one stuck.
+    pub fn leaves_player_stuck(
+        &self,
+        placement: &BlockPlacement,
+        placer_id: CellType,
+        victim: &Player,
+    ) -> bool {
+        let mut board = self.clone();
+        if board.apply(placement, placer_id).is_err() {
+            return false;
+        }
+        let first_block = board.count_cells(victim.player_id) == 0;
+        !board.has_any_legal_placement(victim, first_block)
+    }
+
+    /// Whether any of the player blocks can be placed somewhere on the board.
+    pub fn has_any_legal_placement(&self, player: &Player, first_block: bool) -> bool {
+        if first_block && self.free_cells() == self.nrows() * self.ncols() {
+            // opening on an empty board: the smallest block fitting the top left corner
+            // is enough, skip the brute-force search
+            let smallest_block = player.blocks.iter().min_by_key(|block| block.cells());
+            if smallest_block.is_some_and(|block| {
+                self.can_place(0, 0, block, player.player_id, true)
+                    .placement_ok()
+            }) {
+                return true;
+            }
+        }
+        let fits_free_region = self.free_region_filter();
+        player.blocks.iter().any(|block| {
+            fits_free_region(block)
+                && self
+                    .bruteforce_search_place(block, player.player_id, first_block, None)
+                    .next()
+                    .is_some()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::block::{Block, OrientedPiece};
+    use crate::board::{Board, BoardError, CellType, MoveError, PlacementRuleset, Topology};
+    use crate::strategy::{evaluate, BlockPlacement, Player, ScoringConfig};
+    use crate::theme::Theme;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use std::collections::BTreeSet;
+    use std::sync::{Arc, Mutex};
+
+    /// Occupied cells of every legal placement of `block`, checking every anchor and
+    /// all 8 orientations with [`Board::can_place`].
+    fn reference_legal_placements(
+        board: &Board,
+        block: &Block,
+        player_id: u8,
+        first_block: bool,
+    ) -> BTreeSet<Vec<(usize, usize)>> {
+        let mut placements = BTreeSet::new();
+        for orientation in 0..8 {
+            let (rotation, transposition) = OrientedPiece::rotation_transposition(orientation);
+            for row in 0..board.nrows() {
+                for col in 0..board.ncols() {
+                    let block_placement = BlockPlacement {
+                        block: block.clone(),
+                        row,
+                        col,
+                        rotation,
+                        transposition,
+                    };
+                    let (row, col, oriented) = block_placement.as_row_col_block();
+                    if board
+                        .can_place(row, col, &oriented, player_id, first_block)
+                        .placement_ok()
+                    {
+                        placements.insert(block_placement.occupied_cells());
+                    }
+                }
+            }
+        }
+        placements
+    }
+
+    #[test]
+    fn test_board_apply() {
+        let block = Block::from_str("#  \n###").unwrap();
+        let placement = BlockPlacement {
+            block: block.clone(),
+            row: 2,
+            col: 3,
+            rotation: 1,
+            transposition: 1,
+        };
+
+        let mut board = Board::new(10, 10);
+        let occupied_cells = board.apply(&placement, 1).unwrap();
+
+        let mut expected_board = Board::new(10, 10);
+        expected_board
+            .place(2, 3, &block.transpose().rotate_90(), 1)
+            .unwrap();
+        assert_eq!(board, expected_board);
+
+        // transposed then rotated: "  #\n###"
+        assert_eq!(occupied_cells, vec![(2, 5), (3, 3), (3, 4), (3, 5)]);
+    }
+
+    #[test]
+    fn test_board_legal_placements_match_reference() {
+        let mut rng = StdRng::seed_from_u64(696);
+        let block_set = Block::default_block_set();
+        for _ in 0..40 {
+            let nrows = rng.gen_range(3..=7);
+            let ncols = rng.gen_range(3..=7);
+            let mut board = Board::new(nrows, ncols);
+            for row in 0..nrows {
+                for col in 0..ncols {
+                    // mostly free cells, a few of each player
+                    board.data[(row, col)] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 2][rng.gen_range(0..10)];
+                }
+            }
+            for first_block in [true, false] {
+                for block in block_set.choose_multiple(&mut rng, 4) {
+                    let placements: BTreeSet<Vec<(usize, usize)>> = board
+                        .legal_placements(block, 1, first_block)
+                        .iter()
+                        .map(|position| {
+                            BlockPlacement {
+                                block: block.clone(),
+                                row: position.row,
+                                col: position.col,
+                                rotation: position.rotation,
+                                transposition: position.transposition,
+                            }
+                            .occupied_cells()
+                        })
+                        .collect();
+                    assert_eq!(
+                        placements,
+                        reference_legal_placements(&board, block, 1, first_block),
+                        "{:?} on\n{}",
+                        block,
+                        board
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_apply_and_score() {
+        let mut board = Board::from_str(
+            "1.....\n\
+             ......\n\
+             ......\n\
+             ......\n\
+             ......\n\
+             .....2",
+        )
+        .unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                ..Default::default()
+            })
+            .collect();
+        let placement = BlockPlacement {
+            block: Block::from_str("##\n#.").unwrap(),
+            row: 1,
+            col: 1,
+            rotation: 0,
+            transposition: 0,
+        };
+
+        let mut expected_board = board.clone();
+        expected_board.apply(&placement, 1).unwrap();
+        let expected_score = evaluate(&expected_board, 1, &players);
+        assert_eq!(
+            board.apply_and_score(&placement, 1, &players),
+            Ok(expected_score)
+        );
+        assert_eq!(board, expected_board);
+
+        board.undo(&placement.occupied_cells());
+        assert_eq!(board.count_cells(1), 1);
+        assert_eq!(
+            board.apply_and_score(
+                &BlockPlacement {
+                    row: 5,
+                    col: 4,
+                    ..placement
+                },
+                1,
+                &players
+            ),
+            Err(MoveError::Overlapping)
+        );
+    }
+
+    #[test]
+    fn test_board_can_place_placement() {
+        let block = Block::from_str("#  \n###").unwrap();
+        let mut board = Board::new(10, 10);
+        board
+            .place(0, 0, &Block::from_str("##\n##").unwrap(), 1)
+            .unwrap();
+
+        for transposition in 0..2 {
+            for rotation in 0..4 {
+                let placement = BlockPlacement {
+                    block: block.clone(),
+                    row: 2,
+                    col: 2,
+                    rotation,
+                    transposition,
+                };
+                let oriented_block = if transposition == 0 {
+                    block.clone()
+                } else {
+                    block.transpose()
+                };
+                let oriented_block = (0..rotation).fold(oriented_block, |b, _| b.rotate_90());
+                assert_eq!(
+                    board
+                        .can_place_placement(&placement, 1, false)
+                        .placement_ok(),
+                    board
+                        .can_place(2, 2, &oriented_block, 1, false)
+                        .placement_ok()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_from_str() {
+        let board = Board::from_str("1..\n.2.\n..3").unwrap();
+        assert_eq!(board.nrows(), 3);
+        assert_eq!(board.ncols(), 3);
+        assert_eq!(board.at_row_col(0, 0), 1);
+        assert_eq!(board.at_row_col(1, 1), 2);
+        assert_eq!(board.at_row_col(2, 2), 3);
+        assert_eq!(board.free_cells(), 6);
+
+        assert_eq!(board.to_string(), "1..\n.2.\n..3");
+        assert_eq!(Board::from_str(&board.to_string()).unwrap(), board);
+
+        assert!(Board::from_str("1..\n.2").is_err());
+        assert!(Board::from_str("1.x").is_err());
+        assert!(Board::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_board_cells() {
+        let mut board = Board::new(3, 4);
+        board
+            .place(1, 1, &Block::from_str("##\n#_").unwrap(), 2)
+            .unwrap();
+        let cells: Vec<(usize, usize, CellType)> = board.cells().collect();
+        assert_eq!(cells.len(), 3 * 4);
+        assert_eq!(cells[0], (0, 0, 0));
+        assert_eq!(cells[4 + 1], (1, 1, 2));
+        assert_eq!(cells[11], (2, 3, 0));
+        for (row, col, cell_type) in cells {
+            assert_eq!(cell_type, board.at_row_col(row, col));
+        }
+        assert_eq!(
+            board
+                .cells()
+                .filter(|&(_, _, cell_type)| cell_type == 2)
+                .map(|(row, col, _)| (row, col))
+                .collect::<Vec<_>>(),
+            vec![(1, 1), (1, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_board_compact_string() {
+        let board = Board::from_str("1..\n.42").unwrap();
+        assert_eq!(board.to_compact_string(), "2x3:100042");
+        assert_eq!(Board::from_compact_string("2x3:100042").unwrap(), board);
+        let board = Board::new(20, 20);
+        assert_eq!(
+            Board::from_compact_string(&board.to_compact_string()).unwrap(),
+            board
+        );
+
+        assert!(matches!(
+            Board::from_compact_string("2x3:10004"),
+            Err(BoardError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            Board::from_compact_string("2x3:1000420"),
+            Err(BoardError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            Board::from_compact_string("0x3:"),
+            Err(BoardError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            Board::from_compact_string("2x3:1.0042"),
+            Err(BoardError::InvalidCell('.'))
+        ));
+        assert!(matches!(
+            Board::from_compact_string("2by3:100042"),
+            Err(BoardError::InvalidHeader(_))
+        ));
+        assert!(matches!(
+            Board::from_compact_string("100042"),
+            Err(BoardError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn test_board_zobrist_hash() {
+        let block = Block::from_str("#  \n###").unwrap();
+        let mut board = Board::new(10, 10);
+        board.place(0, 0, &block, 1).unwrap();
+        let mut other_board = Board::new(10, 10);
+        other_board.place(0, 0, &block, 1).unwrap();
+        assert_eq!(board.zobrist_hash(), other_board.zobrist_hash());
+
+        // same cells, different player
+        let mut other_board = Board::new(10, 10);
+        other_board.place(0, 0, &block, 2).unwrap();
+        assert_ne!(board.zobrist_hash(), other_board.zobrist_hash());
+
+        let hash = board.zobrist_hash();
+        let placement = BlockPlacement {
+            block,
+            row: 1,
+            col: 3,
+            rotation: 2,
+            transposition: 0,
+        };
+        let occupied_cells = board.apply(&placement, 1).unwrap();
+        assert_ne!(board.zobrist_hash(), hash);
+        board.undo(&occupied_cells);
+        assert_eq!(board.zobrist_hash(), hash);
+    }
+
+    #[test]
+    fn test_board_canonical() {
+        let board = Board::from_str(
+            "1.....\n\
+             11...2\n\
+             ....22\n\
+             3.....",
+        )
+        .unwrap();
+        assert_eq!(
+            board.rotate_90(),
+            Board::from_str(
+                "3.11\n\
+                 ..1.\n\
+                 ....\n\
+                 ....\n\
+                 .2..\n\
+                 .22.",
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            board.mirror(),
+            Board::from_str(
+                ".....1\n\
+                 2...11\n\
+                 22....\n\
+                 .....3",
+            )
+            .unwrap()
+        );
+        assert_eq!(board.rotate_90().rotate_90().rotate_90().rotate_90(), board);
+        assert_eq!(board.mirror().mirror(), board);
+
+        let rotations = |board: Board| {
+            std::iter::successors(Some(board), |board| Some(board.rotate_90())).take(4)
+        };
+        let variants: Vec<Board> = rotations(board.clone())
+            .chain(rotations(board.mirror()))
+            .collect();
+        let canonical = board.canonical();
+        assert!(variants.contains(&canonical));
+        for variant in &variants {
+            assert_eq!(variant.canonical(), canonical);
+        }
+        // quarter turns change the dimensions of a non square board, the hash only
+        // identifies the variants with the same dimensions
+        for (nrows, ncols) in [(4, 6), (6, 4)] {
+            let same_dimensions: Vec<&Board> = variants
+                .iter()
+                .filter(|variant| variant.nrows() == nrows && variant.ncols() == ncols)
+                .collect();
+            assert_eq!(same_dimensions.len(), 4);
+            let smallest_hash = same_dimensions
+                .iter()
+                .map(|variant| variant.zobrist_hash())
+                .min()
+                .unwrap();
+            for variant in same_dimensions {
+                assert_eq!(variant.symmetric_zobrist_hash(), smallest_hash);
+            }
+        }
+        // player-relative values are kept
+        let players: Vec<Player> = (1..=3)
+            .map(|player_id| Player {
+                player_id,
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(
+            evaluate(&canonical, 2, &players),
+            evaluate(&board, 2, &players)
+        );
+        // another position
+        assert_ne!(board.mirror().rotate_90(), board);
+        assert_ne!(Board::new(4, 6).canonical(), canonical);
+        assert_ne!(
+            board.symmetric_zobrist_hash(),
+            Board::new(4, 6).symmetric_zobrist_hash()
+        );
+    }
+
+    #[test]
+    fn test_board_symmetric_zobrist_hash_non_square() {
+        // transposing a non square board is not a symmetry
+        let horizontal = Board::from_str(".11..\n.....\n.....").unwrap();
+        let vertical = Board::from_str("1....\n1....\n.....").unwrap();
+        assert_ne!(
+            horizontal.symmetric_zobrist_hash(),
+            vertical.symmetric_zobrist_hash()
+        );
+        let board = Board::from_str(".1.....\n.......\n.......\n.......\n.......").unwrap();
+        let other_board = Board::from_str(".......\n..1....\n.......\n.......\n.......").unwrap();
+        assert_ne!(
+            board.symmetric_zobrist_hash(),
+            other_board.symmetric_zobrist_hash()
+        );
+        // the half turn and the mirrors are still symmetries
+        let mirrored = Board::from_str("..11.\n.....\n.....").unwrap();
+        let half_turn = Board::from_str(".....\n.....\n..11.").unwrap();
+        for board in [mirrored, half_turn] {
+            assert_eq!(
+                board.symmetric_zobrist_hash(),
+                horizontal.symmetric_zobrist_hash()
+            );
+        }
+    }
+
+    #[test]
+    fn test_board_empty_regions() {
+        let board = Board::from_str("..1..\n..1..\n111..\n.....").unwrap();
+        let mut region_sizes: Vec<usize> = board.empty_regions().iter().map(|r| r.len()).collect();
+        region_sizes.sort();
+        assert_eq!(region_sizes, vec![4, 11]);
+        assert!(Board::from_str("11\n11")
+            .unwrap()
+            .empty_regions()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_board_largest_empty_region() {
+        let board = Board::from_str("..1..\n..1..\n111..\n.....").unwrap();
+        let region = board.largest_empty_region().unwrap();
+        assert_eq!(region.len(), 11);
+        assert!(region.contains(&(3, 0)));
+        assert!(!region.contains(&(0, 0)));
+        assert_eq!(
+            Board::from_str("11\n11").unwrap().largest_empty_region(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_board_topology() {
+        let monomino = Block::from_str("#").unwrap();
+        let mut grid = Board::new(10, 10);
+        grid.place(2, 9, &monomino, 1).unwrap();
+        let mut torus = grid.clone();
+        torus.set_topology(Topology::Torus);
+
+        // side contact across the left/right edges
+        let rule = grid.can_place(2, 0, &monomino, 1, false);
+        assert_eq!(rule.own_block_touching_sides, Some(false));
+        let rule = torus.can_place(2, 0, &monomino, 1, false);
+        assert_eq!(rule.own_block_touching_sides, Some(true));
+
+        // corner contact across the left/right edges
+        assert!(!grid.can_place(3, 0, &monomino, 1, false).placement_ok());
+        assert!(torus.can_place(3, 0, &monomino, 1, false).placement_ok());
+
+        // away from the edges both topologies agree
+        assert!(grid.can_place(3, 8, &monomino, 1, false).placement_ok());
+        assert!(torus.can_place(3, 8, &monomino, 1, false).placement_ok());
+        assert!(!grid.can_place(2, 8, &monomino, 1, false).placement_ok());
+        assert!(!torus.can_place(2, 8, &monomino, 1, false).placement_ok());
+    }
+
+    #[test]
+    fn test_board_search_cap() {
+        let block = Block::from_str("#").unwrap();
+        // every position is legal
+        let mut board = Board::new(20, 20);
+        board.set_ruleset(PlacementRuleset {
+            first_must_cover_start: false,
+            ..Default::default()
+        });
+        let count = |board: &Board, max_positions| {
+            board
+                .bruteforce_search_place(&block, 1, true, max_positions)
+                .count()
+        };
+        assert_eq!(count(&board, None), 400);
+        assert_eq!(count(&board, Some(10)), 10);
+        assert_eq!(count(&board, Some(0)), 0);
+
+        // only corners are legal, the last one is the last position examined
+        let board = Board::new(20, 20);
+        assert_eq!(count(&board, None), 4);
+        assert_eq!(count(&board, Some(10)), 1);
+        assert_eq!(count(&board, Some(399)), 3);
+    }
+
+    #[test]
+    fn test_board_ruleset() {
+        let pentomino = Block::from_str("##\n##\n#_").unwrap();
+        let mut board = Board::new(20, 20);
+        assert!(!board.can_place(9, 9, &pentomino, 1, true).placement_ok());
+
+        board.set_ruleset(PlacementRuleset {
+            first_must_cover_start: false,
+            ..Default::default()
+        });
+        assert!(board.can_place(9, 9, &pentomino, 1, true).placement_ok());
+        // later blocks still need a corner contact
+        assert!(!board.can_place(9, 9, &pentomino, 1, false).placement_ok());
+        board.place(0, 0, &pentomino, 2).unwrap();
+        assert!(!board.can_place(0, 0, &pentomino, 1, true).placement_ok());
+    }
+
+    #[test]
+    fn test_board_corners() {
+        let board = Board::new(3, 5);
+        assert_eq!(board.corners(), Some([(0, 0), (0, 4), (2, 0), (2, 4)]));
+        assert!(board.is_corner(0, 4));
+        assert!(board.is_corner(2, 0));
+        assert!(!board.is_corner(1, 0));
+        assert!(!board.is_corner(2, 3));
+        assert!(!board.is_corner(3, 5));
+        // a single row has its corners twice
+        assert_eq!(
+            Board::new(1, 2).corners(),
+            Some([(0, 0), (0, 1), (0, 0), (0, 1)])
+        );
+        // e.g. the board of a client before the server sent one
+        for board in [Board::default(), Board::new(0, 0), Board::new(0, 3)] {
+            assert_eq!(board.corners(), None);
+            assert!(!board.is_corner(0, 0));
+        }
+    }
+
+    #[test]
+    fn test_board_has_diagonal_same_color() {
+        let mut board = Board::from_str(
+            "1...\n\
+             ....\n\
+             ..2.\n\
+             ....",
+        )
+        .unwrap();
+        assert!(board.has_diagonal_same_color(1, 1, 1));
+        assert!(board.has_diagonal_same_color(1, 1, 2));
+        assert!(!board.has_diagonal_same_color(1, 0, 1));
+        assert!(!board.has_diagonal_same_color(0, 0, 1));
+        assert!(!board.has_diagonal_same_color(3, 0, 2));
+        assert!(board.has_diagonal_same_color(3, 3, 2));
+
+        // diagonals wrap around the edges of a torus
+        assert!(!board.has_diagonal_same_color(3, 3, 1));
+        board.set_topology(Topology::Torus);
+        assert!(board.has_diagonal_same_color(3, 3, 1));
+    }
+
+    #[test]
+    fn test_board_side_contact_ruleset() {
+        let monomino = Block::from_str("#").unwrap();
+        let domino = Block::from_str("##").unwrap();
+        let mut board = Board::from_str(
+            "1....\n\
+             .....\n\
+             ..2..\n\
+             .....",
+        )
+        .unwrap();
+        // diagonal to player 1 and touching player 2 by a side
+        assert!(board.can_place(1, 1, &domino, 1, false).placement_ok());
+        // diagonal to player 1 and player 2
+        assert!(board.can_place(1, 1, &monomino, 1, false).placement_ok());
+
+        board.set_ruleset(PlacementRuleset {
+            forbid_any_side_contact: true,
+            ..Default::default()
+        });
+        let placement_rule = board.can_place(1, 1, &domino, 1, false);
+        assert!(!placement_rule.placement_ok());
+        assert_eq!(placement_rule.own_block_touching_sides, Some(true));
+        // diagonal contacts with opponents are still allowed
+        assert!(board.can_place(1, 1, &monomino, 1, false).placement_ok());
+    }
+
+    #[test]
+    fn test_board_placeable_pieces() {
+        let player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        };
+        // only two free cells diagonal to player 1
+        let board = Board::from_str("12222\n2..22\n22222").unwrap();
+        let placeable_pieces = board.placeable_pieces(&player, false);
+        assert_eq!(placeable_pieces.len(), player.blocks.len());
+        for (block, placeable) in player.blocks.iter().zip(placeable_pieces) {
+            assert_eq!(placeable, block.cells() <= 2);
+        }
+        assert!(board.has_any_legal_placement(&player, false));
+
+        let board = Board::from_str("12222\n22222\n22222").unwrap();
+        assert!(board
+            .placeable_pieces(&player, false)
+            .iter()
+            .all(|placeable| !placeable));
+        assert!(!board.has_any_legal_placement(&player, false));
+    }
+
+    #[test]
+    fn test_board_free_cells() {
+        let mut board = Board::new(10, 10);
+        assert_eq!(board.free_cells(), 100);
+        assert_eq!(board.fill_ratio(), 0.0);
+
+        board
+            .place(0, 0, &Block::from_str("#####").unwrap(), 1)
+            .unwrap();
+        board
+            .place(9, 5, &Block::from_str("#####").unwrap(), 2)
+            .unwrap();
+        assert_eq!(board.free_cells(), 90);
+        assert!((board.fill_ratio() - 0.1).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_board_score_estimate() {
+        let mut board = Board::new(20, 20);
+        let mut player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        };
+        assert_eq!(
+            board.score_estimate(&player, &ScoringConfig::default()),
+            -89
+        );
+
+        let block_placement = BlockPlacement {
+            block: player.blocks.pop().unwrap(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        board.apply(&block_placement, 1).unwrap();
+        assert_eq!(
+            board.score_estimate(&player, &ScoringConfig::default()),
+            -84
+        );
+
+        // completed hand
+        player.blocks.clear();
+        assert_eq!(
+            board.score_estimate(&player, &ScoringConfig::default()),
+            player.score()
+        );
+        assert_eq!(board.score_estimate(&player, &ScoringConfig::default()), 15);
+    }
+
+    #[test]
+    fn test_board_contiguity_bonus() {
+        let placement = |block: &str, row, col| BlockPlacement {
+            block: Block::from_str(block).unwrap(),
+            row,
+            col,
+            rotation: 0,
+            transposition: 0,
+        };
+        let opening = [
+            (1, placement("##\n##", 0, 0)),
+            (2, placement("###", 7, 5)),
+            (1, placement("#_\n##", 2, 2)),
+            (2, placement("#\n#", 5, 4)),
+        ];
+        let player = |player_id| Player {
+            player_id,
+            blocks: vec![Block::from_str("#").unwrap()],
+            ..Default::default()
+        };
+        let contiguity = ScoringConfig {
+            contiguity_bonus: 2,
+            ..Default::default()
+        };
+        let board = Board::from_placements(8, 8, &opening[..2]).unwrap();
+        assert_eq!(board.score_estimate(&player(1), &contiguity), -1 + 2 * 4);
+        assert_eq!(board.score_estimate(&player(2), &contiguity), -1 + 2 * 3);
+        // each block chained by a corner joins the group
+        let board = Board::from_placements(8, 8, &opening).unwrap();
+        assert_eq!(board.score_estimate(&player(1), &contiguity), -1 + 2 * 7);
+        assert_eq!(board.score_estimate(&player(2), &contiguity), -1 + 2 * 5);
+        // off by default
+        assert_eq!(
+            board.score_estimate(&player(1), &ScoringConfig::default()),
+            player(1).score()
+        );
+        assert_eq!(
+            Board::new(3, 3).score_estimate(&player(1), &contiguity),
+            player(1).score_with(&contiguity)
+        );
+    }
+
+    #[test]
+    fn test_board_print_with_coordinates() {
+        let mut board = Board::new(12, 15);
+        board
+            .place(10, 13, &Block::from_str("#").unwrap(), 2)
+            .unwrap();
+        let printed = board.print_with_coordinates();
+        let lines: Vec<&str> = printed.lines().collect();
+        assert_eq!(lines.len(), 1 + board.nrows());
+
+        let header: Vec<usize> = lines[0]
+            .split_whitespace()
+            .map(|col| col.parse().unwrap())
+            .collect();
+        assert_eq!(header, (0..board.ncols()).collect::<Vec<usize>>());
+        for (row, line) in lines[1..].iter().enumerate() {
+            let cells: Vec<&str> = line.split_whitespace().collect();
+            assert_eq!(cells[0], row.to_string());
+            assert_eq!(cells.len(), 1 + board.ncols());
+            // columns are aligned with the header
+            assert_eq!(line.len(), lines[0].len());
         }
+        assert_eq!(&lines[11][..2], "10");
+        assert_eq!(lines[11].split_whitespace().nth(1 + 13), Some("2"));
+    }
+
+    #[test]
+    fn test_board_place_overlapping() {
+        let mut board = Board::new(5, 5);
+        let occupied_cells = board
+            .place(1, 1, &Block::from_str("##\n#_").unwrap(), 1)
+            .unwrap();
+        assert_eq!(occupied_cells, vec![(1, 1), (1, 2), (2, 1)]);
+        let board_before = board.clone();
+
+        let result = board.place(2, 0, &Block::from_str("###").unwrap(), 2);
+        assert_eq!(result, Err(MoveError::Overlapping));
+        assert_eq!(board, board_before);
+
+        let result = board.place(4, 3, &Block::from_str("###").unwrap(), 2);
+        assert_eq!(result, Err(MoveError::OutOfBounds));
+        assert_eq!(board, board_before);
+    }
+
+    #[test]
+    fn test_board_describe() {
+        let board = Board::from_str(
+            "11...\n\
+             1....\n\
+             .....\n\
+             ....2",
+        )
+        .unwrap();
+        assert_eq!(board.open_corners(1, false), vec![(1, 2), (2, 1)]);
+        assert_eq!(
+            board.describe(1, false),
+            "4 by 5 board, 16 free cells. player 1 has 3 cells, player 2 has 1 cells. \
+             Open corners for player 1: row 1 column 2, row 2 column 1."
+        );
+        assert_eq!(board.open_corners(3, true), vec![(0, 4), (3, 0)]);
+        assert!(board.describe(3, true).contains(
+            "player 3 has 0 cells. Open corners for player 3: row 0 column 4, row 3 column 0."
+        ));
+    }
+
+    #[test]
+    fn test_board_to_svg() {
+        let board = Board::from_str(
+            "11...\n\
+             1....\n\
+             ....2",
+        )
+        .unwrap();
+        let svg = board.to_svg(10, &Theme::default());
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"50\" height=\"30\" viewBox=\"0 0 50 30\""
+        ));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(
+            svg.contains("<rect x=\"40\" y=\"20\" width=\"10\" height=\"10\" fill=\"#00ff00\"/>")
+        );
+    }
+
+    #[test]
+    fn test_board_equal_up_to_player_permutation() {
+        let board = Board::from_str("11.\n.2.\n..3").unwrap();
+        let swapped = Board::from_str("22.\n.1.\n..3").unwrap();
+        assert_ne!(board, swapped);
+        assert!(board.equal_up_to_player_permutation(&swapped));
+        assert!(swapped.equal_up_to_player_permutation(&board));
+        assert!(board.equal_up_to_player_permutation(&board));
+
+        // players 1 and 2 merged
+        let merged = Board::from_str("11.\n.1.\n..3").unwrap();
+        assert!(!board.equal_up_to_player_permutation(&merged));
+        assert!(!merged.equal_up_to_player_permutation(&board));
+        // different occupied cells
+        let moved = Board::from_str("11.\n2..\n..3").unwrap();
+        assert!(!board.equal_up_to_player_permutation(&moved));
+        assert!(!board.equal_up_to_player_permutation(&Board::new(3, 4)));
+    }
+
+    #[test]
+    fn test_board_connected_components_of() {
+        let board = Board::from_str(
+            "11...\n\
+             1.22.\n\
+             .1...\n\
+             .11.2",
+        )
+        .unwrap();
+        assert_eq!(
+            board.connected_components_of(1, false),
+            vec![vec![(0, 0), (0, 1), (1, 0)], vec![(2, 1), (3, 1), (3, 2)]]
+        );
+        assert_eq!(board.connected_components_of(2, false).len(), 2);
+        assert!(board.connected_components_of(3, false).is_empty());
+        // (1, 0) and (2, 1) touch by a corner
+        assert_eq!(
+            board.connected_components_of(1, true),
+            vec![vec![(0, 0), (0, 1), (1, 0), (2, 1), (3, 1), (3, 2)]]
+        );
+        assert_eq!(board.connected_components_of(2, true).len(), 2);
+    }
+
+    #[test]
+    fn test_board_reachable_free_cells() {
+        // player 1 is walled in the top left corner by player 2
+        let board = Board::from_str(
+            "1.2...\n\
+             .22...\n\
+             2.....\n\
+             ......",
+        )
+        .unwrap();
+        assert_eq!(board.reachable_free_cells(1), 0);
+        assert_eq!(board.reachable_free_cells(2), 12);
+        // first block: from the free start corners, (0, 1) and (1, 0) are enclosed
+        assert_eq!(board.reachable_free_cells(3), 17);
+        assert_eq!(Board::new(3, 3).reachable_free_cells(1), 9);
+    }
+
+    #[test]
+    fn test_board_open_corner_count() {
+        let board = Board::from_str(
+            "11....\n\
+             1.....\n\
+             ......\n\
+             ....2.\n\
+             ...222\n\
+             ....2.",
+        )
+        .unwrap();
+        // (1, 2), (2, 1)
+        assert_eq!(board.open_corner_count(1), 2);
+        // (2, 3), (2, 5), (3, 2), (5, 2)
+        assert_eq!(board.open_corner_count(2), 4);
+        // free start corners: (0, 5), (5, 0) and (5, 5)
+        assert_eq!(board.open_corner_count(3), 3);
+    }
+
+    #[test]
+    fn test_board_get() {
+        let board = Board::from_str("1.\n.2\n..").unwrap();
+        assert_eq!(board.get(0, 0), Some(1));
+        assert_eq!(board.get(0, 1), Some(0));
+        assert_eq!(board.get(1, 1), Some(2));
+        assert_eq!(board.get(2, 1), Some(0));
+        assert_eq!(board.get(3, 0), None);
+        assert_eq!(board.get(0, 2), None);
+        assert_eq!(board.get(usize::MAX, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_board_from_placements() {
+        let placement = |block: &str, row, col, rotation| BlockPlacement {
+            block: Block::from_str(block).unwrap(),
+            row,
+            col,
+            rotation,
+            transposition: 0,
+        };
+        let opening = [
+            (1, placement("##\n##", 0, 0, 0)),
+            (2, placement("###", 7, 5, 0)),
+            (1, placement("#_\n##", 2, 2, 0)),
+            (2, placement("#\n#", 5, 4, 0)),
+        ];
+        let mut expected = Board::new(8, 8);
+        for (player_id, placement) in &opening {
+            expected.apply(placement, *player_id).unwrap();
+        }
+        let board = Board::from_placements(8, 8, &opening).unwrap();
+        assert_eq!(board, expected);
+        assert_eq!(
+            board.to_string(),
+            "11......\n\
+             11......\n\
+             ..1.....\n\
+             ..11....\n\
+             ........\n\
+             ....2...\n\
+             ....2...\n\
+             .....222"
+        );
+
+        // player 2 first block not in a corner
+        let mut illegal = opening.clone();
+        illegal[1] = (2, placement("###", 6, 5, 0));
+        assert_eq!(
+            Board::from_placements(8, 8, &illegal),
+            Err(MoveError::IllegalPlacement)
+        );
+        // touching its own block by a side
+        let mut illegal = opening.clone();
+        illegal[2] = (1, placement("#", 2, 0, 0));
+        assert_eq!(
+            Board::from_placements(8, 8, &illegal),
+            Err(MoveError::IllegalPlacement)
+        );
+    }
+
+    #[test]
+    fn test_board_opening_has_legal_placement() {
+        let board = Board::new(20, 20);
+        for player_id in 1..=4 {
+            let player = Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            };
+            assert!(board.has_any_legal_placement(&player, true));
+        }
+        // no block left
+        let player = Player {
+            player_id: 1,
+            ..Default::default()
+        };
+        assert!(!board.has_any_legal_placement(&player, true));
+        // the smallest block does not fill its top left cell: full search
+        let player = Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("_#\n##").unwrap()],
+            ..Default::default()
+        };
+        assert!(board.has_any_legal_placement(&player, true));
+        // too big for the board
+        let player = Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#####").unwrap()],
+            ..Default::default()
+        };
+        assert!(!Board::new(3, 3).has_any_legal_placement(&player, true));
+    }
+
+    #[test]
+    fn test_board_on_change() {
+        let changes = Arc::new(Mutex::new(Vec::<Vec<(usize, usize)>>::new()));
+        let mut board = Board::new(5, 5);
+        let observed = Arc::clone(&changes);
+        board.set_on_change(Box::new(move |cells| {
+            observed.lock().unwrap().push(cells.to_vec())
+        }));
+        let block = Block::from_str("##\n#_").unwrap();
+        let occupied_cells = board.place(1, 2, &block, 1).unwrap();
+        assert_eq!(occupied_cells, vec![(1, 2), (1, 3), (2, 2)]);
+        assert_eq!(*changes.lock().unwrap(), vec![occupied_cells.clone()]);
+
+        // failed placements and clones do not notify
+        assert!(board.place(1, 1, &block, 2).is_err());
+        let mut copy = board.clone();
+        copy.undo(&occupied_cells);
+        assert_eq!(changes.lock().unwrap().len(), 1);
+
+        board.undo(&occupied_cells);
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![occupied_cells.clone(), occupied_cells]
+        );
+        assert_eq!(board, copy);
+    }
+
+    #[test]
+    fn test_board_leaves_player_stuck() {
+        let board = Board::from_str(
+            "1...\n\
+             ....\n\
+             ....\n\
+             ...2",
+        )
+        .unwrap();
+        let victim = Player {
+            player_id: 2,
+            blocks: vec![Block::from_str("#").unwrap()],
+            ..Default::default()
+        };
+        // covers (2, 2), the only open corner of player 2
+        let blocking = BlockPlacement {
+            block: Block::from_str("##\n.#").unwrap(),
+            row: 1,
+            col: 1,
+            rotation: 0,
+            transposition: 0,
+        };
+        assert!(board
+            .can_place_placement(&blocking, 1, false)
+            .placement_ok());
+        assert!(board.leaves_player_stuck(&blocking, 1, &victim));
+        // next to the corner only
+        let harmless = BlockPlacement {
+            block: Block::from_str("##").unwrap(),
+            ..blocking.clone()
+        };
+        assert!(board
+            .can_place_placement(&harmless, 1, false)
+            .placement_ok());
+        assert!(!board.leaves_player_stuck(&harmless, 1, &victim));
+        // the board itself is left unchanged
+        assert_eq!(board.count_cells(1), 1);
     }
 }
@@ -0,0 +1,133 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use crate::input::Action;
+
+/// Seat of a player in the main menu.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub(crate) enum PlayerSetup {
+    Computer,
+    /// with its display name, empty for the default one
+    Human(String),
+    NotSelected,
+}
+
+/// Match setup read from a RON file with `--config`, command line flags take precedence.
+///
+/// Every field is optional, values use the format of the matching flag, e.g.
+/// `(strategy: Some("minimax:3"), scoring: Some("20,0"), colors: {4: "ff8000"})`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ConfigFile {
+    /// `(rows, columns)`, puzzles keep the size of their board
+    pub board_size: Option<(usize, usize)>,
+    /// seats of players 1 to 4 preselected in the main menu
+    pub players: Vec<PlayerSetup>,
+    pub strategy: Option<String>,
+    pub seed: Option<u64>,
+    /// `RRGGBB` color by player id
+    pub colors: BTreeMap<u8, String>,
+    /// keys by action, as in a `--keymap` file
+    pub keymap: HashMap<Action, Vec<String>>,
+    pub turn_order: Vec<u8>,
+    pub scoring: Option<String>,
+    pub overtime: Option<String>,
+    pub contiguity_bonus: Option<i32>,
+    pub torus: bool,
+    pub free_start: bool,
+    pub grid_lines: bool,
+    pub bars: bool,
+}
+
+impl ConfigFile {
+    pub fn from_ron(s: &str) -> Result<ConfigFile, ron::error::SpannedError> {
+        ron::from_str(s)
+    }
+}
+
+/// Puzzle read from a `.ron` file with `--puzzle`, e.g.
+/// `(board: "..22\n..22\n2222", pieces: Some("##\n##\n\n#"))`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PuzzleFile {
+    /// one line per row, `.` for a free cell, `2`-`9` for obstacles
+    pub board: String,
+    /// hand of the player in the `--pieces` format, the `--pieces` set if `None`
+    #[serde(default)]
+    pub pieces: Option<String>,
+}
+
+impl PuzzleFile {
+    pub fn from_ron(s: &str) -> Result<PuzzleFile, ron::error::SpannedError> {
+        ron::from_str(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::{ConfigFile, PlayerSetup, PuzzleFile};
+    use crate::input::Action;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn test_config_file_from_ron() {
+        let config = ConfigFile::from_ron(
+            "(
+                board_size: Some((14, 14)),
+                players: [Human(\"Ada\"), Computer, NotSelected, Human(\"\")],
+                strategy: Some(\"minimax:3\"),
+                seed: Some(42),
+                colors: {4: \"ff8000\"},
+                keymap: {Rotate: [\"x\"]},
+                turn_order: [3, 1],
+                scoring: Some(\"20,0\"),
+                overtime: Some(\"10,1\"),
+                contiguity_bonus: Some(2),
+                torus: true,
+                free_start: true,
+                grid_lines: true,
+                bars: true,
+            )",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            ConfigFile {
+                board_size: Some((14, 14)),
+                players: vec![
+                    PlayerSetup::Human(String::from("Ada")),
+                    PlayerSetup::Computer,
+                    PlayerSetup::NotSelected,
+                    PlayerSetup::Human(String::new()),
+                ],
+                strategy: Some(String::from("minimax:3")),
+                seed: Some(42),
+                colors: BTreeMap::from([(4, String::from("ff8000"))]),
+                keymap: HashMap::from([(Action::Rotate, vec![String::from("x")])]),
+                turn_order: vec![3, 1],
+                scoring: Some(String::from("20,0")),
+                overtime: Some(String::from("10,1")),
+                contiguity_bonus: Some(2),
+                torus: true,
+                free_start: true,
+                grid_lines: true,
+                bars: true,
+            }
+        );
+
+        assert_eq!(ConfigFile::from_ron("()").unwrap(), ConfigFile::default());
+        assert!(ConfigFile::from_ron("(board: Some((14, 14)))").is_err());
+        assert!(ConfigFile::from_ron("(players: [Robot])").is_err());
+    }
+
+    #[test]
+    fn test_puzzle_file_from_ron() {
+        let puzzle = PuzzleFile::from_ron("(board: \"..2\\n222\", pieces: Some(\"#\"))").unwrap();
+        assert_eq!(puzzle.board, "..2\n222");
+        assert_eq!(puzzle.pieces, Some(String::from("#")));
+        let puzzle = PuzzleFile::from_ron("(board: \"..\")").unwrap();
+        assert_eq!(puzzle.pieces, None);
+        assert!(PuzzleFile::from_ron("(pieces: Some(\"#\"))").is_err());
+    }
+}
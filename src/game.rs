@@ -0,0 +1,1245 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::block::Block;
+use crate::board::{Board, MoveError};
+use crate::strategy::{
+    evaluate, strategy_from_name, BlockPlacement, Player, ScoringConfig, Strategy,
+};
+
+/// A move played by a player, with what is needed to undo it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct MoveRecord {
+    pub player_id: u8,
+    /// hand index and block removed from the player blocks
+    pub removed_block: Option<(usize, Block)>,
+    /// board cells occupied by the placed block
+    pub occupied_cells: Vec<(usize, usize)>,
+    /// whether the player got eliminated, i.e. did not place any block
+    pub eliminated: bool,
+    /// last placed block of the player before this move
+    pub previous_last_placed: Option<Block>,
+    /// overtime added to the player for this move, see [`Player::add_overtime`]
+    pub overtime: Duration,
+}
+
+/// Play `block_placement` for `player`, the player is eliminated if there is none.
+///
+/// A placement the board rejects leaves the board and the player untouched.
+pub(crate) fn play_move(
+    board: &mut Board,
+    player: &mut Player,
+    block_placement: Option<BlockPlacement>,
+    players_eliminated: &mut HashSet<u8>,
+) -> Result<MoveRecord, MoveError> {
+    let mut move_record = MoveRecord {
+        player_id: player.player_id,
+        removed_block: None,
+        occupied_cells: vec![],
+        eliminated: false,
+        previous_last_placed: player.last_placed.clone(),
+        overtime: Duration::ZERO,
+    };
+    if let Some(block_placement) = block_placement {
+        move_record.occupied_cells = board.apply(&block_placement, player.player_id)?;
+        // remove block from player blocks, the placed block may be a rotated copy
+        if let Some(index) = player.hand_index(&block_placement.block) {
+            move_record.removed_block = Some((index, player.blocks.remove(index)));
+        }
+        player.last_placed = Some(block_placement.block);
+    } else {
+        move_record.eliminated = players_eliminated.insert(player.player_id);
+    }
+    Ok(move_record)
+}
+
+/// Eliminate `player` at any time, forfeiting its remaining pieces: the others keep playing.
+///
+/// Unlike passing with [`play_move`], fails if the player is already out of the game.
+pub(crate) fn resign_move(
+    board: &mut Board,
+    player: &mut Player,
+    players_eliminated: &mut HashSet<u8>,
+) -> Result<MoveRecord, MoveError> {
+    if players_eliminated.contains(&player.player_id) {
+        return Err(MoveError::AlreadyEliminated(player.player_id));
+    }
+    play_move(board, player, None, players_eliminated)
+}
+
+/// Revert a move returned by [`play_move`]: free the board cells, give the block back to
+/// the player, take its overtime back and un-eliminate the player.
+pub(crate) fn undo_move(
+    board: &mut Board,
+    player: &mut Player,
+    move_record: MoveRecord,
+    players_eliminated: &mut HashSet<u8>,
+) {
+    board.undo(&move_record.occupied_cells);
+    if let Some((index, block)) = move_record.removed_block {
+        player.blocks.insert(index, block);
+    }
+    player.last_placed = move_record.previous_last_placed;
+    player.overtime = player.overtime.saturating_sub(move_record.overtime);
+    if move_record.eliminated {
+        players_eliminated.remove(&move_record.player_id);
+    }
+}
+
+/// Game settings chosen before the first move.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct GameConfig {
+    /// player ids in play order, players not listed play after them by increasing id
+    pub turn_order: Vec<u8>,
+    pub scoring: ScoringConfig,
+}
+
+impl GameConfig {
+    /// Ids of `players` in play order.
+    pub fn play_order(&self, players: &[Player]) -> Vec<u8> {
+        let mut player_ids: Vec<u8> = players.iter().map(|p| p.player_id).collect();
+        player_ids.sort_by_key(|player_id| {
+            // ordered ids first, then the others by id
+            let position = self.turn_order.iter().position(|id| id == player_id);
+            (position.unwrap_or(self.turn_order.len()), *player_id)
+        });
+        player_ids
+    }
+}
+
+/// What happened after a move accepted by [`GameEngine::play`].
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum MoveOutcome {
+    /// the block was placed on these board cells
+    Placed(Vec<(usize, usize)>),
+    /// the player passed and is out of the game
+    Eliminated,
+}
+
+/// Authoritative game state: board, players and whose turn it is.
+///
+/// Unlike the TUI game loop, moves are validated before being played.
+pub(crate) struct GameEngine {
+    board: Board,
+    players: Vec<Player>,
+    players_eliminated: HashSet<u8>,
+    turn_counter: usize,
+    current_position: usize,
+    /// moves played so far, in play order
+    moves: Vec<MoveRecord>,
+}
+
+impl GameEngine {
+    pub fn new(board: Board, players: Vec<Player>) -> Self {
+        GameEngine {
+            board,
+            players,
+            players_eliminated: HashSet::new(),
+            turn_counter: 0,
+            current_position: 0,
+            moves: vec![],
+        }
+    }
+
+    /// Engine where players take turns in the order of `config`.
+    #[cfg(test)]
+    pub fn with_config(board: Board, mut players: Vec<Player>, config: &GameConfig) -> Self {
+        let play_order = config.play_order(&players);
+        players.sort_by_key(|p| play_order.iter().position(|&id| id == p.player_id));
+        GameEngine::new(board, players)
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// Blocks still in the hand of `player_id`, empty for unknown players.
+    #[cfg(test)]
+    pub fn hand(&self, player_id: u8) -> &[Block] {
+        self.players
+            .iter()
+            .find(|p| p.player_id == player_id)
+            .map_or(&[], |p| p.blocks.as_slice())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.players_eliminated.len() >= self.players.len()
+    }
+
+    /// Whether players are placing their first block.
+    pub fn is_first_turn(&self) -> bool {
+        self.turn_counter == 0
+    }
+
+    /// Moves played with [`GameEngine::play`], in play order.
+    pub fn moves(&self) -> &[MoveRecord] {
+        &self.moves
+    }
+
+    /// Id of the player expected to play next, `None` once the game is over.
+    pub fn current_player_id(&self) -> Option<u8> {
+        if self.is_over() {
+            None
+        } else {
+            Some(self.players[self.current_position].player_id)
+        }
+    }
+
+    /// Play `block_placement` for `player_id`, passing (`None`) eliminates the player.
+    pub fn play(
+        &mut self,
+        player_id: u8,
+        block_placement: Option<BlockPlacement>,
+    ) -> Result<MoveOutcome, MoveError> {
+        let current_player_id = self.current_player_id().ok_or(MoveError::GameOver)?;
+        if player_id != current_player_id {
+            return Err(MoveError::NotYourTurn(player_id));
+        }
+        let player = &mut self.players[self.current_position];
+        if let Some(block_placement) = &block_placement {
+            // each copy of a piece leaves the hand once played, whatever its orientation
+            if player.hand_index(&block_placement.block).is_none() {
+                return Err(MoveError::PieceNotInHand(player_id));
+            }
+            if !self
+                .board
+                .can_place_placement(block_placement, player_id, self.turn_counter == 0)
+                .placement_ok()
+            {
+                return Err(MoveError::IllegalPlacement);
+            }
+        }
+        let move_record = play_move(
+            &mut self.board,
+            player,
+            block_placement,
+            &mut self.players_eliminated,
+        )?;
+        self.advance();
+        let outcome = if move_record.eliminated {
+            MoveOutcome::Eliminated
+        } else {
+            MoveOutcome::Placed(move_record.occupied_cells.clone())
+        };
+        self.moves.push(move_record);
+        Ok(outcome)
+    }
+
+    /// Score of each player in play order with `scoring`.
+    ///
+    /// Eliminated players are scored like the others: the pieces they never placed count
+    /// against them, whether they ran out of moves, passed or resigned.
+    #[cfg(test)]
+    pub fn scores(&self, scoring: &ScoringConfig) -> Vec<(u8, i32)> {
+        self.players
+            .iter()
+            .map(|p| (p.player_id, self.board.score_estimate(p, scoring)))
+            .collect()
+    }
+
+    /// Eliminate `player_id` at any time with [`resign_move`], e.g. a player leaving, the
+    /// others keep playing.
+    #[cfg(any(test, feature = "net"))]
+    pub fn resign(&mut self, player_id: u8) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+        let position = self
+            .players
+            .iter()
+            .position(|p| p.player_id == player_id)
+            .ok_or(MoveError::UnknownPlayer(player_id))?;
+        let move_record = resign_move(
+            &mut self.board,
+            &mut self.players[position],
+            &mut self.players_eliminated,
+        )?;
+        self.moves.push(move_record);
+        if position == self.current_position {
+            self.advance();
+        }
+        Ok(())
+    }
+
+    /// Move on to the next player still in the game.
+    fn advance(&mut self) {
+        if self.is_over() {
+            return;
+        }
+        loop {
+            self.current_position += 1;
+            if self.current_position == self.players.len() {
+                self.current_position = 0;
+                self.turn_counter += 1;
+            }
+            let player_id = self.players[self.current_position].player_id;
+            if !self.players_eliminated.contains(&player_id) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(any(test, feature = "net"))]
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub(crate) enum VerifyError {
+    #[error("move {index}: {source}")]
+    IllegalMove { index: usize, source: MoveError },
+}
+
+/// Replay `moves` from `board` and `players` through a [`GameEngine`], checking that each
+/// was legal, in turn order and played a piece still in hand, `None` being a pass.
+///
+/// Returns the index of the first offending move.
+#[cfg(any(test, feature = "net"))]
+pub(crate) fn verify_game(
+    board: Board,
+    players: Vec<Player>,
+    moves: &[(u8, Option<BlockPlacement>)],
+) -> Result<(), VerifyError> {
+    let mut engine = GameEngine::new(board, players);
+    for (index, (player_id, block_placement)) in moves.iter().enumerate() {
+        engine
+            .play(*player_id, block_placement.clone())
+            .map_err(|source| VerifyError::IllegalMove { index, source })?;
+    }
+    Ok(())
+}
+
+/// Play a whole game between computer players without any UI, until every player is eliminated.
+///
+/// Players without a strategy are eliminated on their first turn.
+/// Returns the moves in play order, or the error of the first move the [`GameEngine`]
+/// rejected.
+pub(crate) fn play_headless(
+    board: &mut Board,
+    players: &mut [Player],
+    strategies: &mut HashMap<u8, Box<dyn Strategy>>,
+) -> Result<Vec<MoveRecord>, MoveError> {
+    let mut engine = GameEngine::new(board.clone(), players.to_vec());
+    while let Some(player_id) = engine.current_player_id() {
+        let block_placement = strategies.get_mut(&player_id).and_then(|strategy| {
+            strategy.place(
+                engine.board(),
+                player_id,
+                engine.players(),
+                engine.is_first_turn(),
+            )
+        });
+        engine.play(player_id, block_placement)?;
+    }
+    board.clone_from(engine.board());
+    players.clone_from_slice(engine.players());
+    Ok(engine.moves().to_vec())
+}
+
+/// Game between `player_count` computer players using `strategy_name`, each seeded from
+/// `seed` like in the interactive game: the same seed replays the same game.
+///
+/// Returns `None` for an unknown strategy or a strategy playing an illegal move.
+pub(crate) fn demo_game(
+    board: &mut Board,
+    player_count: u8,
+    block_set: &[Block],
+    strategy_name: &str,
+    seed: u64,
+) -> Option<(Vec<Player>, Vec<MoveRecord>)> {
+    let mut players: Vec<Player> = (1..=player_count)
+        .map(|player_id| Player {
+            player_id,
+            blocks: block_set.to_vec(),
+            ..Default::default()
+        })
+        .collect();
+    let mut strategies = players
+        .iter()
+        .map(|p| {
+            strategy_from_name(strategy_name, seed.wrapping_add(p.player_id as u64))
+                .map(|strategy| (p.player_id, strategy))
+        })
+        .collect::<Option<HashMap<u8, Box<dyn Strategy>>>>()?;
+    let moves = play_headless(board, &mut players, &mut strategies).ok()?;
+    Some((players, moves))
+}
+
+/// Results of one of the strategies of a [`simulate`] run.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct StrategyResult {
+    pub name: String,
+    /// games where no player scored more, ties count as a win for each tied player
+    pub wins: usize,
+    pub average_score: f64,
+}
+
+/// Results of [`simulate`], printed as JSON by `--simulate`, e.g. `{"games":2,"seed":7,
+/// "strategies":[{"name":"greedy","wins":2,"average_score":-12.5}]}`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct SimulationSummary {
+    pub games: usize,
+    pub seed: u64,
+    /// in the order of the simulated strategy names
+    pub strategies: Vec<StrategyResult>,
+}
+
+/// Play `games` games on copies of `board` between 1 to 4 computer players, one per
+/// strategy name, and total the results of each strategy.
+///
+/// Strategies change seats every game so none always plays first, each game is seeded
+/// from `seed`: the same arguments give the same summary.
+/// Returns `None` for an unknown strategy, more than 4 strategies or a strategy playing an
+/// illegal move.
+pub(crate) fn simulate(
+    board: &Board,
+    games: usize,
+    seed: u64,
+    strategy_names: &[&str],
+    block_set: &[Block],
+    scoring: &ScoringConfig,
+) -> Option<SimulationSummary> {
+    if strategy_names.is_empty() || strategy_names.len() > 4 {
+        return None;
+    }
+    let mut wins = vec![0; strategy_names.len()];
+    let mut total_scores = vec![0; strategy_names.len()];
+    for game in 0..games {
+        let seat_strategy = |seat: usize| (seat + game) % strategy_names.len();
+        let mut players: Vec<Player> = (1..=strategy_names.len() as u8)
+            .map(|player_id| Player {
+                player_id,
+                blocks: block_set.to_vec(),
+                ..Default::default()
+            })
+            .collect();
+        let mut game_strategies = players
+            .iter()
+            .enumerate()
+            .map(|(seat, p)| {
+                let strategy_seed = seed.wrapping_add(game as u64 * 4 + p.player_id as u64);
+                strategy_from_name(strategy_names[seat_strategy(seat)], strategy_seed)
+                    .map(|strategy| (p.player_id, strategy))
+            })
+            .collect::<Option<HashMap<u8, Box<dyn Strategy>>>>()?;
+        let mut game_board = board.clone();
+        play_headless(&mut game_board, &mut players, &mut game_strategies).ok()?;
+        let scores: Vec<i32> = players
+            .iter()
+            .map(|player| game_board.score_estimate(player, scoring))
+            .collect();
+        let best_score = scores.iter().copied().max().unwrap_or(0);
+        for (seat, score) in scores.into_iter().enumerate() {
+            total_scores[seat_strategy(seat)] += score;
+            if score == best_score {
+                wins[seat_strategy(seat)] += 1;
+            }
+        }
+    }
+    let strategies = strategy_names
+        .iter()
+        .zip(wins)
+        .zip(total_scores)
+        .map(|((name, wins), total_score)| StrategyResult {
+            name: name.to_string(),
+            wins,
+            average_score: total_score as f64 / games.max(1) as f64,
+        })
+        .collect();
+    Some(SimulationSummary {
+        games,
+        seed,
+        strategies,
+    })
+}
+
+/// One line per move: the player id then the cells it occupied, or `pass`.
+pub(crate) fn move_log(moves: &[MoveRecord]) -> String {
+    moves
+        .iter()
+        .map(|move_record| {
+            let cells = if move_record.occupied_cells.is_empty() {
+                String::from("pass")
+            } else {
+                move_record
+                    .occupied_cells
+                    .iter()
+                    .map(|(row, col)| format!("{},{}", row, col))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            format!("{}: {}\n", move_record.player_id, cells)
+        })
+        .collect()
+}
+
+/// Board metrics of a player and its opponents, compared before and after a move to
+/// comment it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct MoveMetrics {
+    pub own_cells: usize,
+    pub own_corners: usize,
+    /// open corners of all the opponents
+    pub opponent_corners: usize,
+    /// most free cells an opponent could still claim, see [`Board::reachable_free_cells`]
+    pub opponent_reachable: usize,
+}
+
+impl MoveMetrics {
+    pub fn of(board: &Board, player_id: u8, players: &[Player]) -> Self {
+        let opponents = players.iter().filter(|p| p.player_id != player_id);
+        MoveMetrics {
+            own_cells: board.count_cells(player_id),
+            own_corners: board.open_corner_count(player_id),
+            opponent_corners: opponents
+                .clone()
+                .map(|p| board.open_corner_count(p.player_id))
+                .sum(),
+            opponent_reachable: opponents
+                .map(|p| board.reachable_free_cells(p.player_id))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// One-line label of a move from the metrics of its player before and after it.
+///
+/// A move cutting an opponent off more free cells than it covers seals a region for its
+/// player, otherwise taking opponent corners matters most, then the player own corners.
+pub(crate) fn move_label(before: &MoveMetrics, after: &MoveMetrics) -> String {
+    let placed_cells = after.own_cells.saturating_sub(before.own_cells);
+    if placed_cells == 0 {
+        return String::from("passes");
+    }
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    let sealed_cells = before
+        .opponent_reachable
+        .saturating_sub(after.opponent_reachable);
+    if sealed_cells > placed_cells {
+        String::from("seals own region")
+    } else if after.opponent_corners < before.opponent_corners {
+        String::from("denies opponent")
+    } else if after.own_corners > before.own_corners {
+        let opened = after.own_corners - before.own_corners;
+        format!("opens {} corner{}", opened, plural(opened))
+    } else if after.own_corners < before.own_corners {
+        let closed = before.own_corners - after.own_corners;
+        format!("closes {} corner{}", closed, plural(closed))
+    } else {
+        String::from("keeps its corners")
+    }
+}
+
+/// Evaluation delta for its player and label of each of `moves`, replaying them backwards
+/// from the `board` they led to.
+pub(crate) fn move_commentary(
+    board: &Board,
+    players: &[Player],
+    moves: &[MoveRecord],
+) -> Vec<(i32, String)> {
+    let mut board = board.clone();
+    let mut commentary: Vec<(i32, String)> = moves
+        .iter()
+        .rev()
+        .map(|move_record| {
+            let player_id = move_record.player_id;
+            let evaluation_after = evaluate(&board, player_id, players);
+            let after = MoveMetrics::of(&board, player_id, players);
+            board.undo(&move_record.occupied_cells);
+            let evaluation_before = evaluate(&board, player_id, players);
+            let before = MoveMetrics::of(&board, player_id, players);
+            (
+                evaluation_after - evaluation_before,
+                move_label(&before, &after),
+            )
+        })
+        .collect();
+    commentary.reverse();
+    commentary
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+    use std::time::Duration;
+
+    use crate::block::Block;
+    use crate::board::{Board, MoveError};
+    use crate::game::{
+        demo_game, move_commentary, move_label, move_log, play_headless, play_move, simulate,
+        undo_move, verify_game, GameConfig, GameEngine, MoveMetrics, MoveOutcome, MoveRecord,
+        SimulationSummary, StrategyResult, VerifyError,
+    };
+    use crate::strategy::{strategy_from_name, BlockPlacement, Player, ScoringConfig, Strategy};
+
+    #[test]
+    fn test_undo_move() {
+        let mut board = Board::new(20, 20);
+        board
+            .place(0, 0, &Block::from_str("##\n##").unwrap(), 2)
+            .unwrap();
+        let mut player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        };
+        let mut players_eliminated = HashSet::new();
+        let board_before = board.clone();
+        let blocks_before = player.blocks.clone();
+
+        let block_placement = BlockPlacement {
+            block: player.blocks[5].clone(),
+            row: 18,
+            col: 17,
+            rotation: 0,
+            transposition: 0,
+        };
+        let mut move_record = play_move(
+            &mut board,
+            &mut player,
+            Some(block_placement),
+            &mut players_eliminated,
+        )
+        .unwrap();
+        assert_ne!(board, board_before);
+        assert_eq!(player.blocks.len(), blocks_before.len() - 1);
+        let scoring = ScoringConfig {
+            turn_budget: Some(Duration::from_secs(10)),
+            ..Default::default()
+        };
+        move_record.overtime = player.add_overtime(Duration::from_secs(12), &scoring);
+        assert_eq!(player.overtime, Duration::from_secs(2));
+
+        undo_move(
+            &mut board,
+            &mut player,
+            move_record,
+            &mut players_eliminated,
+        );
+        assert_eq!(board, board_before);
+        assert_eq!(player.blocks, blocks_before);
+        assert_eq!(player.last_placed, None);
+        assert_eq!(player.overtime, Duration::ZERO);
+
+        // undoing an elimination
+        let move_record =
+            play_move(&mut board, &mut player, None, &mut players_eliminated).unwrap();
+        assert!(players_eliminated.contains(&1));
+        undo_move(
+            &mut board,
+            &mut player,
+            move_record,
+            &mut players_eliminated,
+        );
+        assert!(players_eliminated.is_empty());
+        assert_eq!(board, board_before);
+        assert_eq!(player.blocks, blocks_before);
+
+        // a rejected placement changes nothing
+        let overlapping = BlockPlacement {
+            block: player.blocks[0].clone(),
+            row: 1,
+            col: 1,
+            rotation: 0,
+            transposition: 0,
+        };
+        assert_eq!(
+            play_move(
+                &mut board,
+                &mut player,
+                Some(overlapping),
+                &mut players_eliminated
+            ),
+            Err(MoveError::Overlapping)
+        );
+        assert_eq!(board, board_before);
+        assert_eq!(player.blocks, blocks_before);
+        assert_eq!(player.last_placed, None);
+    }
+
+    #[test]
+    fn test_play_move_rotated_block() {
+        let mut board = Board::new(20, 20);
+        let mut player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        };
+        let blocks_before = player.blocks.clone();
+        let mut players_eliminated = HashSet::new();
+
+        // e.g. a placement received over the network holding a rotated copy of an L4
+        let block_placement = BlockPlacement {
+            block: player.blocks[5].rotate_90().transpose(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        let move_record = play_move(
+            &mut board,
+            &mut player,
+            Some(block_placement),
+            &mut players_eliminated,
+        )
+        .unwrap();
+        assert_eq!(
+            move_record.removed_block,
+            Some((5, blocks_before[5].clone()))
+        );
+        assert_eq!(player.blocks.len(), blocks_before.len() - 1);
+
+        undo_move(
+            &mut board,
+            &mut player,
+            move_record,
+            &mut players_eliminated,
+        );
+        assert_eq!(player.blocks, blocks_before);
+    }
+
+    #[test]
+    fn test_game_engine() {
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        let mut engine = GameEngine::new(Board::new(20, 20), players);
+        let monomino = Block::from_str("#").unwrap();
+        let placement = |row, col| BlockPlacement {
+            block: monomino.clone(),
+            row,
+            col,
+            rotation: 0,
+            transposition: 0,
+        };
+
+        assert_eq!(engine.current_player_id(), Some(1));
+        assert_eq!(
+            engine.play(2, Some(placement(0, 0))),
+            Err(MoveError::NotYourTurn(2))
+        );
+        assert_eq!(
+            engine.play(1, Some(placement(5, 5))),
+            Err(MoveError::IllegalPlacement)
+        );
+        assert_eq!(
+            engine.play(1, Some(placement(0, 0))),
+            Ok(MoveOutcome::Placed(vec![(0, 0)]))
+        );
+        assert_eq!(engine.current_player_id(), Some(2));
+        // the monomino is not in player 1 hand anymore
+        assert_eq!(engine.play(2, None), Ok(MoveOutcome::Eliminated));
+        assert_eq!(
+            engine.play(1, Some(placement(1, 1))),
+            Err(MoveError::PieceNotInHand(1))
+        );
+        assert_eq!(engine.play(1, None), Ok(MoveOutcome::Eliminated));
+        assert!(engine.is_over());
+        assert_eq!(engine.current_player_id(), None);
+        assert_eq!(engine.play(1, None), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn test_game_engine_rejects_reused_piece() {
+        let l3 = Block::from_str("##\n#_").unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![l3.clone(), l3.clone(), Block::from_str("#").unwrap()],
+                ..Default::default()
+            })
+            .collect();
+        let mut engine = GameEngine::new(Board::new(8, 8), players);
+        let placement = |row, col, rotation| BlockPlacement {
+            block: l3.clone(),
+            row,
+            col,
+            rotation,
+            transposition: 0,
+        };
+        assert!(engine.play(1, Some(placement(0, 0, 0))).is_ok());
+        assert!(engine.play(2, Some(placement(6, 6, 2))).is_ok());
+        // the second copy of the piece
+        assert!(engine.play(1, Some(placement(2, 1, 0))).is_ok());
+        assert!(engine.play(2, None).is_ok());
+
+        // a third one, even rotated, is rejected and nothing changes
+        let board = engine.board().clone();
+        assert_eq!(
+            engine.play(1, Some(placement(4, 4, 1))),
+            Err(MoveError::PieceNotInHand(1))
+        );
+        assert_eq!(engine.board(), &board);
+        assert_eq!(engine.hand(1), &[Block::from_str("#").unwrap()]);
+        assert_eq!(engine.current_player_id(), Some(1));
+    }
+
+    #[test]
+    fn test_verify_game() {
+        let monomino = Block::from_str("#").unwrap();
+        let domino = Block::from_str("##").unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![monomino.clone(), domino.clone()],
+                ..Default::default()
+            })
+            .collect();
+        let placement = |block: &Block, row, col| {
+            Some(BlockPlacement {
+                block: block.clone(),
+                row,
+                col,
+                rotation: 0,
+                transposition: 0,
+            })
+        };
+        let moves = vec![
+            (1, placement(&monomino, 0, 0)),
+            (2, placement(&monomino, 4, 4)),
+            (1, placement(&domino, 1, 1)),
+            (2, placement(&domino, 3, 2)),
+            (1, None),
+            (2, None),
+        ];
+        let verify = |moves: &[(u8, Option<BlockPlacement>)]| {
+            verify_game(Board::new(5, 5), players.clone(), moves)
+        };
+        assert_eq!(verify(&moves), Ok(()));
+
+        let mut illegal = moves.clone();
+        // not touching player 1 monomino by a corner
+        illegal[2] = (1, placement(&domino, 2, 2));
+        assert_eq!(
+            verify(&illegal),
+            Err(VerifyError::IllegalMove {
+                index: 2,
+                source: MoveError::IllegalPlacement
+            })
+        );
+
+        let mut duplicate = moves.clone();
+        // the monomino was already played
+        duplicate[2] = (1, placement(&monomino, 1, 1));
+        assert_eq!(
+            verify(&duplicate),
+            Err(VerifyError::IllegalMove {
+                index: 2,
+                source: MoveError::PieceNotInHand(1)
+            })
+        );
+
+        let mut out_of_turn = moves.clone();
+        out_of_turn.swap(0, 1);
+        assert_eq!(
+            verify(&out_of_turn),
+            Err(VerifyError::IllegalMove {
+                index: 0,
+                source: MoveError::NotYourTurn(2)
+            })
+        );
+    }
+
+    /// Many seeded games between computer players, every move checked by the engine.
+    #[test]
+    fn test_self_play_soak() {
+        let board_sizes = [(5, 5), (7, 9), (10, 10), (12, 8)];
+        for seed in 0..12u64 {
+            let player_count = 2 + (seed % 3) as u8;
+            let (nrows, ncols) = board_sizes[seed as usize % board_sizes.len()];
+            let players: Vec<Player> = (1..=player_count)
+                .map(|player_id| Player {
+                    player_id,
+                    blocks: Block::default_block_set(),
+                    ..Default::default()
+                })
+                .collect();
+            let mut strategies: HashMap<u8, Box<dyn Strategy>> = players
+                .iter()
+                .map(|p| {
+                    let seed = seed.wrapping_add(p.player_id as u64);
+                    let name = if seed % 2 == 0 { "greedy" } else { "random" };
+                    (p.player_id, strategy_from_name(name, seed).unwrap())
+                })
+                .collect();
+            // every player places each block or passes once
+            let max_moves = players.len() * (Block::default_block_set().len() + 1);
+            let mut engine = GameEngine::new(Board::new(nrows, ncols), players);
+            let mut moves = 0;
+            while let Some(player_id) = engine.current_player_id() {
+                assert!(moves < max_moves, "game {} did not end", seed);
+                let block_placement = strategies.get_mut(&player_id).unwrap().place(
+                    &engine.board,
+                    player_id,
+                    &engine.players,
+                    engine.turn_counter == 0,
+                );
+                if let Err(e) = engine.play(player_id, block_placement) {
+                    panic!("game {} move {}: {}", seed, moves, e);
+                }
+                moves += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_engine_hand() {
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        let mut engine = GameEngine::new(Board::new(20, 20), players);
+        let block_count = Block::default_block_set().len();
+        let monomino = Block::from_str("#").unwrap();
+        assert_eq!(engine.hand(1).len(), block_count);
+        assert!(engine.hand(1).contains(&monomino));
+
+        let placement = BlockPlacement {
+            block: monomino.clone(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        assert!(engine.play(1, Some(placement)).is_ok());
+        assert_eq!(engine.hand(1).len(), block_count - 1);
+        assert!(!engine.hand(1).contains(&monomino));
+        assert_eq!(engine.hand(2).len(), block_count);
+        assert_eq!(engine.players()[0].blocks, engine.hand(1));
+        assert!(engine.hand(3).is_empty());
+    }
+
+    #[test]
+    fn test_game_engine_scores() {
+        let monomino = Block::from_str("#").unwrap();
+        let domino = Block::from_str("##").unwrap();
+        let players: Vec<Player> = (1..=3)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![domino.clone(), monomino.clone()],
+                ..Default::default()
+            })
+            .collect();
+        let mut engine = GameEngine::new(Board::new(5, 5), players);
+        let placement = |block: &Block, row, col| {
+            Some(BlockPlacement {
+                block: block.clone(),
+                row,
+                col,
+                rotation: 0,
+                transposition: 0,
+            })
+        };
+        let scoring = ScoringConfig::default();
+
+        // player 1 places everything, the monomino last
+        assert!(engine.play(1, placement(&domino, 0, 0)).is_ok());
+        // player 2 is eliminated early, player 3 after its first block
+        assert_eq!(engine.play(2, None), Ok(MoveOutcome::Eliminated));
+        assert!(engine.play(3, placement(&domino, 4, 3)).is_ok());
+        assert!(engine.play(1, placement(&monomino, 1, 2)).is_ok());
+        assert_eq!(engine.play(3, None), Ok(MoveOutcome::Eliminated));
+        assert_eq!(engine.play(1, None), Ok(MoveOutcome::Eliminated));
+        assert!(engine.is_over());
+        assert_eq!(engine.scores(&scoring), vec![(1, 20), (2, -3), (3, -1)]);
+
+        let scoring = ScoringConfig {
+            all_placed_bonus: 10,
+            monomino_last_bonus: 0,
+            ..Default::default()
+        };
+        assert_eq!(engine.scores(&scoring), vec![(1, 10), (2, -3), (3, -1)]);
+    }
+
+    #[test]
+    fn test_game_engine_resign() {
+        let players: Vec<Player> = (1..=3)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        let mut engine = GameEngine::new(Board::new(20, 20), players);
+        let monomino = Block::from_str("#").unwrap();
+        let placement = |row, col| {
+            Some(BlockPlacement {
+                block: monomino.clone(),
+                row,
+                col,
+                rotation: 0,
+                transposition: 0,
+            })
+        };
+
+        // player 2 leaves while player 1 is playing
+        assert_eq!(engine.resign(2), Ok(()));
+        assert_eq!(engine.players_eliminated, HashSet::from([2]));
+        assert_eq!(engine.resign(2), Err(MoveError::AlreadyEliminated(2)));
+        assert_eq!(engine.resign(5), Err(MoveError::UnknownPlayer(5)));
+        assert_eq!(engine.current_player_id(), Some(1));
+        assert!(engine.play(1, placement(0, 0)).is_ok());
+        assert_eq!(engine.current_player_id(), Some(3));
+        assert_eq!(engine.moves().len(), 2);
+        assert!(engine.moves()[0].eliminated);
+        // the current player resigns
+        assert_eq!(engine.resign(3), Ok(()));
+        assert_eq!(engine.players_eliminated, HashSet::from([2, 3]));
+        assert!(!engine.is_over());
+        assert_eq!(engine.current_player_id(), Some(1));
+        assert!(engine.play(1, placement(19, 19)).is_err());
+        assert_eq!(engine.resign(1), Ok(()));
+        assert!(engine.is_over());
+        assert_eq!(engine.resign(1), Err(MoveError::GameOver));
+    }
+
+    #[test]
+    fn test_game_config_turn_order() {
+        let players: Vec<Player> = (1..=4)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(GameConfig::default().play_order(&players), vec![1, 2, 3, 4]);
+        let config = GameConfig {
+            turn_order: vec![3, 1],
+            ..Default::default()
+        };
+        assert_eq!(config.play_order(&players), vec![3, 1, 2, 4]);
+        // ids without a player are ignored
+        let config = GameConfig {
+            turn_order: vec![4, 9, 2, 3, 1],
+            ..Default::default()
+        };
+        assert_eq!(config.play_order(&players), vec![4, 2, 3, 1]);
+
+        let mut engine = GameEngine::with_config(Board::new(20, 20), players, &config);
+        assert_eq!(engine.current_player_id(), Some(4));
+        assert_eq!(engine.play(1, None), Err(MoveError::NotYourTurn(1)));
+        assert_eq!(engine.play(4, None), Ok(MoveOutcome::Eliminated));
+        assert_eq!(engine.current_player_id(), Some(2));
+        assert_eq!(engine.play(2, None), Ok(MoveOutcome::Eliminated));
+        assert_eq!(engine.play(3, None), Ok(MoveOutcome::Eliminated));
+        assert_eq!(engine.current_player_id(), Some(1));
+        let monomino = Block::from_str("#").unwrap();
+        let placement = BlockPlacement {
+            block: monomino,
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        assert_eq!(
+            engine.play(1, Some(placement)),
+            Ok(MoveOutcome::Placed(vec![(0, 0)]))
+        );
+        // eliminated players are skipped
+        assert_eq!(engine.current_player_id(), Some(1));
+    }
+
+    #[test]
+    fn test_simulation_summary_json() {
+        let summary = SimulationSummary {
+            games: 2,
+            seed: 7,
+            strategies: vec![StrategyResult {
+                name: String::from("say \"hi\"\n"),
+                wins: 1,
+                average_score: -12.5,
+            }],
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&summary).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "games": 2,
+                "seed": 7,
+                "strategies": [{"name": "say \"hi\"\n", "wins": 1, "average_score": -12.5}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_simulate() {
+        let board = Board::new(10, 10);
+        let block_set = Block::default_block_set();
+        let summary = simulate(
+            &board,
+            3,
+            7,
+            &["greedy", "random"],
+            &block_set,
+            &ScoringConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(summary.strategies.len(), 2);
+        // at least one winner per game
+        let wins: usize = summary.strategies.iter().map(|r| r.wins).sum();
+        assert!((3..=6).contains(&wins));
+        let again = simulate(
+            &board,
+            3,
+            7,
+            &["greedy", "random"],
+            &block_set,
+            &ScoringConfig::default(),
+        );
+        assert_eq!(again.as_ref(), Some(&summary));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&summary).unwrap()).unwrap();
+        assert_eq!(json["games"], 3);
+        assert_eq!(json["seed"], 7);
+        let strategies = json["strategies"].as_array().unwrap();
+        assert_eq!(strategies.len(), 2);
+        assert_eq!(strategies[0]["name"], "greedy");
+        assert_eq!(strategies[1]["name"], "random");
+        for (strategy, result) in strategies.iter().zip(&summary.strategies) {
+            assert_eq!(strategy["wins"].as_u64(), Some(result.wins as u64));
+            assert_eq!(
+                strategy["average_score"].as_f64(),
+                Some(result.average_score)
+            );
+        }
+
+        assert!(simulate(
+            &board,
+            1,
+            7,
+            &["unknown"],
+            &block_set,
+            &ScoringConfig::default()
+        )
+        .is_none());
+        assert!(simulate(&board, 1, 7, &[], &block_set, &ScoringConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_demo_game_replay() {
+        let demo_log = |seed| {
+            let mut board = Board::new(10, 10);
+            let (_, moves) =
+                demo_game(&mut board, 4, &Block::default_block_set(), "random", seed).unwrap();
+            move_log(&moves)
+        };
+        let log = demo_log(7);
+        assert!(log.starts_with("1: "));
+        assert!(log.ends_with("4: pass\n"));
+        assert_eq!(log.as_bytes(), demo_log(7).as_bytes());
+        assert_ne!(log, demo_log(8));
+        assert!(demo_game(&mut Board::new(10, 10), 2, &[], "unknown", 7).is_none());
+    }
+
+    #[test]
+    fn test_move_label() {
+        let before = MoveMetrics {
+            own_cells: 5,
+            own_corners: 3,
+            opponent_corners: 6,
+            opponent_reachable: 40,
+        };
+        let label = |after: MoveMetrics| move_label(&before, &after);
+        assert_eq!(label(before), "passes");
+        let after = MoveMetrics {
+            own_cells: 9,
+            ..before
+        };
+        assert_eq!(label(after), "keeps its corners");
+        assert_eq!(
+            label(MoveMetrics {
+                own_corners: 5,
+                ..after
+            }),
+            "opens 2 corners"
+        );
+        assert_eq!(
+            label(MoveMetrics {
+                own_corners: 4,
+                ..after
+            }),
+            "opens 1 corner"
+        );
+        assert_eq!(
+            label(MoveMetrics {
+                own_corners: 1,
+                ..after
+            }),
+            "closes 2 corners"
+        );
+        assert_eq!(
+            label(MoveMetrics {
+                own_corners: 5,
+                opponent_corners: 4,
+                ..after
+            }),
+            "denies opponent"
+        );
+        // covering reachable cells is not sealing, cutting more cells off is
+        assert_eq!(
+            label(MoveMetrics {
+                opponent_reachable: 36,
+                ..after
+            }),
+            "keeps its corners"
+        );
+        assert_eq!(
+            label(MoveMetrics {
+                opponent_corners: 4,
+                opponent_reachable: 30,
+                ..after
+            }),
+            "seals own region"
+        );
+
+        let mut board = Board::new(10, 10);
+        let (players, moves) =
+            demo_game(&mut board, 2, &Block::default_block_set(), "greedy", 7).unwrap();
+        let commentary = move_commentary(&board, &players, &moves);
+        assert_eq!(commentary.len(), moves.len());
+        // the first move opens the corners of an empty board player
+        assert!(commentary[0].0 > 0);
+        assert_eq!(commentary.last().unwrap(), &(0, String::from("passes")));
+    }
+
+    fn seeded_game(seed: u64) -> Vec<MoveRecord> {
+        let mut board = Board::new(10, 10);
+        let mut players: Vec<Player> = (1..=4)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        let mut strategies: HashMap<u8, Box<dyn Strategy>> = players
+            .iter()
+            .map(|p| {
+                let strategy =
+                    strategy_from_name("random", seed.wrapping_add(p.player_id as u64)).unwrap();
+                (p.player_id, strategy)
+            })
+            .collect();
+        play_headless(&mut board, &mut players, &mut strategies).unwrap()
+    }
+
+    #[test]
+    fn test_play_headless_seed() {
+        let moves = seeded_game(42);
+        assert!(moves.iter().any(|m| !m.occupied_cells.is_empty()));
+        assert_eq!(moves, seeded_game(42));
+        assert_ne!(moves, seeded_game(43));
+    }
+}
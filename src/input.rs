@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::io::Result;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseButton, MouseEventKind};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// What a key does during a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub(crate) enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    CyclePrev,
+    CycleNext,
+    Rotate,
+    Transpose,
+    /// mirror the piece left to right
+    Reflect,
+    /// next of the distinct orientations, rotations and transpositions combined
+    CycleOrientation,
+    ToggleAssist,
+    /// show row and column indexes along the board edges
+    ToggleCoordinates,
+    /// select the suggested piece and move it to the suggested placement
+    Suggest,
+    /// show older moves in the move log
+    ScrollLogUp,
+    /// show more recent moves in the move log
+    ScrollLogDown,
+    Describe,
+    Undo,
+    Confirm,
+    /// the player is out of the game, the others keep playing
+    Resign,
+    Quit,
+}
+
+impl Action {
+    /// `(row, col)` move of the movement actions.
+    pub fn movement(self) -> Option<(i32, i32)> {
+        match self {
+            Action::MoveUp => Some((-1, 0)),
+            Action::MoveDown => Some((1, 0)),
+            Action::MoveLeft => Some((0, -1)),
+            Action::MoveRight => Some((0, 1)),
+            _ => None,
+        }
+    }
+}
+
+/// What the player did during a game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Input {
+    /// a key bound to an action was pressed
+    Action(Action),
+    /// the mouse moved over the terminal cell at `(column, row)`, `click` for a left click
+    Pointer { column: u16, row: u16, click: bool },
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum KeyMapError {
+    #[error("Invalid keymap: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Unknown key {0:?}")]
+    UnknownKey(String),
+}
+
+/// Keys bound to each [`Action`].
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct KeyMap {
+    actions: HashMap<KeyCode, Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let actions = [
+            (KeyCode::Up, Action::MoveUp),
+            (KeyCode::Down, Action::MoveDown),
+            (KeyCode::Left, Action::MoveLeft),
+            (KeyCode::Right, Action::MoveRight),
+            (KeyCode::Char('j'), Action::CyclePrev),
+            (KeyCode::Char('k'), Action::CycleNext),
+            (KeyCode::Char('r'), Action::Rotate),
+            (KeyCode::Char('t'), Action::Transpose),
+            (KeyCode::Char('f'), Action::Reflect),
+            (KeyCode::Tab, Action::CycleOrientation),
+            (KeyCode::Char(' '), Action::CycleOrientation),
+            (KeyCode::Char('a'), Action::ToggleAssist),
+            (KeyCode::Char('c'), Action::ToggleCoordinates),
+            (KeyCode::Char('h'), Action::Suggest),
+            (KeyCode::Char('['), Action::ScrollLogUp),
+            (KeyCode::Char(']'), Action::ScrollLogDown),
+            (KeyCode::Char('d'), Action::Describe),
+            (KeyCode::Char('u'), Action::Undo),
+            (KeyCode::Enter, Action::Confirm),
+            (KeyCode::Char('g'), Action::Resign),
+            (KeyCode::Char('q'), Action::Quit),
+        ];
+        KeyMap {
+            actions: actions.into_iter().collect(),
+        }
+    }
+}
+
+/// Key from its name in a keymap file: a single character or e.g. `"Up"`, `"Enter"`.
+fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    match name {
+        "Up" => Some(KeyCode::Up),
+        "Down" => Some(KeyCode::Down),
+        "Left" => Some(KeyCode::Left),
+        "Right" => Some(KeyCode::Right),
+        "Enter" => Some(KeyCode::Enter),
+        "Esc" => Some(KeyCode::Esc),
+        "Tab" => Some(KeyCode::Tab),
+        "Backspace" => Some(KeyCode::Backspace),
+        _ => None,
+    }
+}
+
+impl KeyMap {
+    /// Default keymap with the actions listed in a RON map rebound,
+    /// e.g. `{Rotate: ["x"], Quit: ["Esc", "q"]}`.
+    pub fn from_ron(s: &str) -> std::result::Result<KeyMap, KeyMapError> {
+        KeyMap::from_bindings(ron::from_str(s)?)
+    }
+
+    /// Default keymap with the given actions rebound to key names.
+    pub fn from_bindings(
+        bindings: HashMap<Action, Vec<String>>,
+    ) -> std::result::Result<KeyMap, KeyMapError> {
+        let mut keymap = KeyMap::default();
+        for (action, key_names) in bindings {
+            keymap.actions.retain(|_, a| *a != action);
+            for key_name in key_names {
+                let key_code = key_code_from_name(&key_name)
+                    .ok_or_else(|| KeyMapError::UnknownKey(key_name.clone()))?;
+                keymap.actions.insert(key_code, action);
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn action_for(&self, key_code: KeyCode) -> Option<Action> {
+        self.actions.get(&key_code).copied()
+    }
+}
+
+/// Read all the pending events, waiting up to `timeout` for the first one.
+pub(crate) fn drain_events(timeout: Duration) -> Result<Vec<Event>> {
+    let mut events = vec![];
+    if event::poll(timeout)? {
+        events.push(event::read()?);
+        while event::poll(Duration::ZERO)? {
+            events.push(event::read()?);
+        }
+    }
+    Ok(events)
+}
+
+/// Inputs of `events`, in order: pressed keys bound in `keymap`, mouse moves and left clicks.
+pub(crate) fn inputs(keymap: &KeyMap, events: &[Event]) -> Vec<Input> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                keymap.action_for(key.code).map(Input::Action)
+            }
+            Event::Mouse(mouse) => {
+                let click = match mouse.kind {
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => false,
+                    MouseEventKind::Down(MouseButton::Left) => true,
+                    _ => return None,
+                };
+                Some(Input::Pointer {
+                    column: mouse.column,
+                    row: mouse.row,
+                    click,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the terminal was resized, the whole screen must then be redrawn since
+/// content drawn at the previous size may be left over.
+pub(crate) fn needs_full_redraw(events: &[Event]) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, Event::Resize(_, _)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::{inputs, needs_full_redraw, Action, Input, KeyMap};
+    use ratatui::crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    };
+
+    fn key_event(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind,
+            state: KeyEventState::NONE,
+        })
+    }
+
+    #[test]
+    fn test_coalesce_events() {
+        let events = vec![
+            key_event(KeyCode::Right, KeyEventKind::Press),
+            key_event(KeyCode::Right, KeyEventKind::Release),
+            key_event(KeyCode::Right, KeyEventKind::Press),
+            key_event(KeyCode::Right, KeyEventKind::Repeat),
+            Event::FocusGained,
+            key_event(KeyCode::Down, KeyEventKind::Press),
+            key_event(KeyCode::Char('r'), KeyEventKind::Press),
+            key_event(KeyCode::Char('x'), KeyEventKind::Press),
+            key_event(KeyCode::Left, KeyEventKind::Press),
+        ];
+        let keymap = KeyMap::default();
+        // unbound keys are dropped, the others keep their order
+        assert_eq!(
+            inputs(&keymap, &events),
+            vec![
+                Input::Action(Action::MoveRight),
+                Input::Action(Action::MoveRight),
+                Input::Action(Action::MoveDown),
+                Input::Action(Action::Rotate),
+                Input::Action(Action::MoveLeft)
+            ]
+        );
+        assert_eq!(Action::MoveLeft.movement(), Some((0, -1)));
+        assert_eq!(Action::Rotate.movement(), None);
+        assert!(!needs_full_redraw(&events));
+    }
+
+    #[test]
+    fn test_mouse_inputs() {
+        let mouse_event = |kind, column, row| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let events = vec![
+            mouse_event(MouseEventKind::Moved, 4, 2),
+            key_event(KeyCode::Enter, KeyEventKind::Press),
+            mouse_event(MouseEventKind::ScrollDown, 4, 2),
+            mouse_event(MouseEventKind::Down(MouseButton::Left), 6, 3),
+            mouse_event(MouseEventKind::Down(MouseButton::Right), 6, 3),
+        ];
+        assert_eq!(
+            inputs(&KeyMap::default(), &events),
+            vec![
+                Input::Pointer {
+                    column: 4,
+                    row: 2,
+                    click: false
+                },
+                Input::Action(Action::Confirm),
+                Input::Pointer {
+                    column: 6,
+                    row: 3,
+                    click: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_needs_full_redraw() {
+        let events = vec![
+            key_event(KeyCode::Right, KeyEventKind::Press),
+            Event::Resize(80, 24),
+            key_event(KeyCode::Left, KeyEventKind::Press),
+        ];
+        assert!(needs_full_redraw(&events));
+        // keys pressed along with the resize are still handled
+        assert_eq!(
+            inputs(&KeyMap::default(), &events),
+            vec![
+                Input::Action(Action::MoveRight),
+                Input::Action(Action::MoveLeft)
+            ]
+        );
+        assert!(!needs_full_redraw(&[]));
+        assert!(!needs_full_redraw(&[Event::FocusLost]));
+    }
+
+    #[test]
+    fn test_keymap() {
+        let keymap = KeyMap::default();
+        assert_eq!(keymap.action_for(KeyCode::Char('r')), Some(Action::Rotate));
+        assert_eq!(keymap.action_for(KeyCode::Char('x')), None);
+
+        let keymap = KeyMap::from_ron("{Rotate: [\"x\"], MoveUp: [\"w\", \"Up\"]}").unwrap();
+        assert_eq!(keymap.action_for(KeyCode::Char('x')), Some(Action::Rotate));
+        assert_eq!(keymap.action_for(KeyCode::Char('r')), None);
+        assert_eq!(keymap.action_for(KeyCode::Char('w')), Some(Action::MoveUp));
+        assert_eq!(keymap.action_for(KeyCode::Up), Some(Action::MoveUp));
+        // other actions keep their default keys
+        assert_eq!(
+            keymap.action_for(KeyCode::Char('t')),
+            Some(Action::Transpose)
+        );
+        assert_eq!(
+            keymap
+                .action_for(KeyCode::Char('w'))
+                .and_then(Action::movement),
+            Some((-1, 0))
+        );
+
+        assert!(KeyMap::from_ron("{Rotate: [\"PageUp\"]}").is_err());
+        assert!(KeyMap::from_ron("{Fly: [\"f\"]}").is_err());
+    }
+}
@@ -1,19 +1,19 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{stdout, Result};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use ratatui::buffer::Buffer;
 use ratatui::layout::Constraint::{Fill, Length, Min};
-use ratatui::layout::{Layout, Rect};
-use ratatui::style::Color;
-use ratatui::text::{Line, Text};
+use ratatui::layout::{Flex, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-    Borders, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+    Borders, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap,
 };
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
-        event::{self, KeyCode, KeyEventKind},
+        event::{self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEventKind},
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
@@ -22,38 +22,67 @@ use ratatui::{
 
 use strategy::Player;
 
-use crate::block::Block;
-use crate::board::Board;
-use crate::strategy::{BlockPlacement, GreedyStrategy, Strategy};
+use crate::block::{Block, OrientedPiece};
+use crate::board::{Board, PlacementRuleset, Topology};
+use crate::config::{ConfigFile, PlayerSetup, PuzzleFile};
+use crate::game::{
+    demo_game, move_commentary, move_log, play_move, resign_move, simulate, undo_move, GameConfig,
+};
+use crate::input::{drain_events, inputs, needs_full_redraw, Action, Input, KeyMap};
+use crate::strategy::{
+    regret, strategy_from_name, suggest_move, BlockPlacement, GreedyStrategy, Personality,
+    ScoringConfig, Strategy,
+};
+use crate::theme::Theme;
 
 mod block;
 mod board;
+mod config;
+mod game;
+mod input;
+#[cfg(feature = "net")]
+mod net;
 mod strategy;
+mod theme;
 
-#[derive(Debug, Default)]
-struct BoardWidget {
-    board: Board,
+/// Minimum terminal size to render the game: board, hand and status lines.
+const MIN_TERMINAL_WIDTH: u16 = 80;
+const MIN_TERMINAL_HEIGHT: u16 = 24;
+
+fn terminal_too_small(area: Rect) -> bool {
+    area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT
 }
 
-#[derive(Debug, Default)]
-struct PlayerWidget {
-    player: Player,
+/// size of a board cell in exported SVG images
+const SVG_CELL_PX: usize = 20;
+/// How long the last placed block is highlighted.
+const FLASH_DURATION: Duration = Duration::from_millis(500);
+/// How long a computer move takes to appear, cell by cell.
+const REVEAL_DURATION: Duration = Duration::from_millis(240);
+const NOTICE_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+struct BoardWidget<'a> {
+    board: &'a Board,
+    grid_lines: bool,
+    highlighted_cells: &'a [(usize, usize)],
+    /// placed cells not revealed yet, drawn as free cells
+    hidden_cells: &'a [(usize, usize)],
+    theme: &'a Theme,
+}
+
+#[derive(Debug)]
+struct PlayerWidget<'a> {
+    player: &'a Player,
+    /// whether each block can be placed, blocks without entry are placeable
+    placeable: &'a [bool],
+    theme: &'a Theme,
 }
 
 #[derive(Debug, Default)]
 struct BlockPlacementWidget {
     block_placement: Option<BlockPlacement>,
-    player_id: u8,
-}
-
-fn color_from_player_id(player_id: u8) -> Color {
-    match player_id {
-        1 => Color::Rgb(255, 0, 0),
-        2 => Color::Rgb(0, 255, 0),
-        3 => Color::Rgb(0, 0, 255),
-        4 => Color::Rgb(255, 255, 0),
-        _ => Color::Rgb(0, 0, 0),
-    }
+    color: Color,
 }
 
 impl Widget for &mut BlockPlacementWidget {
@@ -70,8 +99,7 @@ impl Widget for &mut BlockPlacementWidget {
                     let block_col = xi / 2;
                     let block_row = yi;
                     if block.cell_at_row_col(block_row, block_col) {
-                        let color = color_from_player_id(self.player_id);
-                        buf.get_mut(x, y).set_char('█').set_fg(color);
+                        buf.get_mut(x, y).set_char('█').set_fg(self.color);
                     }
                 }
             }
@@ -79,54 +107,109 @@ impl Widget for &mut BlockPlacementWidget {
     }
 }
 
-impl Widget for &mut PlayerWidget {
+impl Widget for PlayerWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
-        let mut dx = 0;
         let mut dy = 0;
-        for block in self.player.blocks.iter() {
-            let block_width_with_margin = (block.ncols() + 1) * 2;
-            let block_height_with_margin = block.nrows() + 1;
-            let enough_h_space =
-                (area.left() + dx + (block_width_with_margin as u16)) < area.right();
-            if !enough_h_space {
-                // try next row
-                dx = 0;
-                dy += block_height_with_margin as u16;
+        for (cells, blocks) in self.player.pieces_by_size() {
+            // each size starts a new line, below its header
+            if area.top() + dy < area.bottom() {
+                buf.set_stringn(
+                    area.left(),
+                    area.top() + dy,
+                    format!("size {}", cells),
+                    area.width as usize,
+                    Style::default().fg(Color::DarkGray),
+                );
             }
-            let enough_h_space =
-                (area.left() + dx + (block_width_with_margin as u16)) < area.right();
-            let enough_v_space =
-                (area.top() + dy + (block_height_with_margin as u16)) < area.bottom();
-            if enough_h_space && enough_v_space {
-                for (xi, x) in ((area.left() + dx)
-                    ..(area.left() + dx + (block_width_with_margin as u16)))
-                    .enumerate()
-                {
-                    for (yi, y) in ((area.top() + dy)
-                        ..(area.top() + dy + (block_height_with_margin as u16)))
+            dy += 1;
+            // every piece of the group is drawn centered in the same square slot
+            let slot = blocks
+                .iter()
+                .map(|(_, block)| {
+                    let (height, width) = block.bounding_box();
+                    height.max(width)
+                })
+                .max()
+                .unwrap_or(0);
+            let mut dx = 0;
+            let mut row_height = 0;
+            for (block_index, block) in blocks {
+                let block = block
+                    .padded_to(slot, slot)
+                    .expect("the slot fits every piece of the group");
+                let block_width_with_margin = (block.ncols() + 1) * 2;
+                let block_height_with_margin = block.nrows() + 1;
+                let enough_h_space =
+                    (area.left() + dx + (block_width_with_margin as u16)) < area.right();
+                if !enough_h_space {
+                    // try next row
+                    dx = 0;
+                    dy += row_height;
+                    row_height = 0;
+                }
+                row_height = row_height.max(block_height_with_margin as u16);
+                let enough_h_space =
+                    (area.left() + dx + (block_width_with_margin as u16)) < area.right();
+                let enough_v_space =
+                    (area.top() + dy + (block_height_with_margin as u16)) < area.bottom();
+                if enough_h_space && enough_v_space {
+                    for (xi, x) in ((area.left() + dx)
+                        ..(area.left() + dx + (block_width_with_margin as u16)))
                         .enumerate()
                     {
-                        let row = yi;
-                        let col = xi / 2;
-                        if (row < block.nrows())
-                            && (col < block.ncols())
-                            && block.cell_at_row_col(row, col)
+                        for (yi, y) in ((area.top() + dy)
+                            ..(area.top() + dy + (block_height_with_margin as u16)))
+                            .enumerate()
                         {
-                            let color = color_from_player_id(self.player.player_id);
-                            buf.get_mut(x, y).set_char('█').set_fg(color);
+                            let row = yi;
+                            let col = xi / 2;
+                            if (row < block.nrows())
+                                && (col < block.ncols())
+                                && block.cell_at_row_col(row, col)
+                            {
+                                let color = if self.placeable.get(block_index) == Some(&false) {
+                                    Color::DarkGray
+                                } else {
+                                    self.theme.color(self.player.player_id)
+                                };
+                                buf.get_mut(x, y).set_char('█').set_fg(color);
+                            }
                         }
                     }
                 }
+                dx += block_width_with_margin as u16;
             }
-            dx += block_width_with_margin as u16;
+            dy += row_height;
         }
     }
 }
 
-impl Widget for &mut BoardWidget {
+/// Whether the cell at `row`, `col` differs from its right and bottom neighbors.
+///
+/// Returns `(right_edge, bottom_edge)`, board borders are not considered edges.
+fn cell_edges(board: &Board, row: usize, col: usize) -> (bool, bool) {
+    let cell_type = board.at_row_col(row, col);
+    let right_edge = col + 1 < board.ncols() && board.at_row_col(row, col + 1) != cell_type;
+    let bottom_edge = row + 1 < board.nrows() && board.at_row_col(row + 1, col) != cell_type;
+    (right_edge, bottom_edge)
+}
+
+/// Glyph for the left or right half of a board cell, leaving a gap at its edges.
+fn cell_glyph(left_half: bool, right_edge: bool, bottom_edge: bool) -> char {
+    match (left_half, right_edge, bottom_edge) {
+        (true, _, false) => '█',
+        (true, _, true) => '▀',
+        (false, false, false) => '█',
+        (false, true, false) => '▌',
+        (false, false, true) => '▀',
+        (false, true, true) => '▘',
+    }
+}
+
+impl Widget for BoardWidget<'_> {
     /// Render the widget
     fn render(self, area: Rect, buf: &mut Buffer) {
         for (xi, x) in (area.left()..area.right()).enumerate() {
@@ -134,9 +217,23 @@ impl Widget for &mut BoardWidget {
                 let board_row = yi;
                 let board_col = xi / 2;
                 if board_col < self.board.ncols() && board_row < self.board.nrows() {
-                    let cell_type = self.board.at_row_col(board_row, board_col);
-                    let color = color_from_player_id(cell_type);
-                    buf.get_mut(x, y).set_char('█').set_fg(color);
+                    let cell_type = if self.hidden_cells.contains(&(board_row, board_col)) {
+                        0
+                    } else {
+                        self.board.at_row_col(board_row, board_col)
+                    };
+                    let color = self.theme.color(cell_type);
+                    let glyph = if self.grid_lines {
+                        let (right_edge, bottom_edge) =
+                            cell_edges(self.board, board_row, board_col);
+                        cell_glyph(xi % 2 == 0, right_edge, bottom_edge)
+                    } else {
+                        '█'
+                    };
+                    let cell = buf.get_mut(x, y).set_char(glyph).set_fg(color);
+                    if self.highlighted_cells.contains(&(board_row, board_col)) {
+                        cell.set_char('▓').set_bg(Color::White);
+                    }
                 }
             }
         }
@@ -155,6 +252,10 @@ enum PlayerSelectionStatus {
 struct PlayerSelectionItem {
     status: PlayerSelectionStatus,
     player_id: u8,
+    /// display name, editable for human players
+    name: String,
+    /// preset of a computer player, `None` plays the `--strategy` strategy
+    personality: Option<Personality>,
 }
 
 impl From<&PlayerSelectionItem> for ListItem<'_> {
@@ -185,14 +286,18 @@ impl From<&PlayerSelectionItem> for ListItem<'_> {
         };
 
         let line = match value.status {
-            PlayerSelectionStatus::Computer => {
-                Line::styled(format!(" Computer     Player {}", value.player_id), color)
-            }
+            PlayerSelectionStatus::Computer => match value.personality {
+                Some(personality) => Line::styled(
+                    format!(" Computer     {} ({})", value.name, personality.name()),
+                    color,
+                ),
+                None => Line::styled(format!(" Computer     {}", value.name), color),
+            },
             PlayerSelectionStatus::Human => {
-                Line::styled(format!(" Human        Player {}", value.player_id), color)
+                Line::styled(format!(" Human        {}", value.name), color)
             }
             PlayerSelectionStatus::NotSelected => {
-                Line::styled(format!(" Not selected Player {}", value.player_id), color)
+                Line::styled(format!(" Not selected {}", value.name), color)
             }
         };
         ListItem::new(line)
@@ -203,35 +308,89 @@ impl From<&PlayerSelectionItem> for ListItem<'_> {
 struct PlayerSelectionList {
     items: Vec<PlayerSelectionItem>,
     state: ListState,
+    /// keys type the name of the selected human player
+    editing_name: bool,
 }
 
 impl Default for PlayerSelectionList {
     fn default() -> Self {
-        let items = vec![
-            PlayerSelectionItem {
-                status: PlayerSelectionStatus::Computer,
-                player_id: 1,
-            },
-            PlayerSelectionItem {
+        let items = (1..=4)
+            .map(|player_id| PlayerSelectionItem {
                 status: PlayerSelectionStatus::Computer,
-                player_id: 2,
-            },
-            PlayerSelectionItem {
-                status: PlayerSelectionStatus::Computer,
-                player_id: 3,
-            },
-            PlayerSelectionItem {
-                status: PlayerSelectionStatus::Computer,
-                player_id: 4,
-            },
-        ];
+                player_id,
+                name: Player::default_name(player_id),
+                personality: None,
+            })
+            .collect();
         PlayerSelectionList {
             items,
             state: ListState::default(),
+            editing_name: false,
+        }
+    }
+}
+
+const MAX_NAME_LENGTH: usize = 16;
+
+/// Edit `name` with a key typed in the name entry, returns whether the entry goes on:
+/// characters are appended, backspace erases, enter or escape confirm.
+fn edit_name(name: &mut String, key_code: KeyCode) -> bool {
+    match key_code {
+        KeyCode::Char(c) => {
+            if name.chars().count() < MAX_NAME_LENGTH {
+                name.push(c);
+            }
+            true
         }
+        KeyCode::Backspace => {
+            name.pop();
+            true
+        }
+        KeyCode::Enter | KeyCode::Esc => false,
+        _ => true,
+    }
+}
+
+/// Preset following `personality` in the main menu, back to `None` after the last one.
+fn next_personality(personality: Option<Personality>) -> Option<Personality> {
+    match personality {
+        None => Personality::ALL.first().copied(),
+        Some(personality) => Personality::ALL
+            .into_iter()
+            .skip_while(|&p| p != personality)
+            .nth(1),
+    }
+}
+
+/// Strategy of the computer player `player_id`: its preset picked in the main menu if any,
+/// `strategy_name` otherwise.
+fn computer_strategy(
+    items: &[PlayerSelectionItem],
+    player_id: u8,
+    strategy_name: &str,
+    seed: u64,
+) -> Option<Box<dyn Strategy>> {
+    match items
+        .iter()
+        .find(|item| item.player_id == player_id)
+        .and_then(|item| item.personality)
+    {
+        Some(personality) => Some(personality.strategy(seed)),
+        None => strategy_from_name(strategy_name, seed),
     }
 }
 
+/// Classic Blokus or a single-player puzzle on a pre-seeded board.
+///
+/// In puzzle mode, the human plays player 1 and tries to place as many cells as possible,
+/// other player ids are obstacles.
+#[derive(Default, Debug, PartialEq)]
+enum GameMode {
+    #[default]
+    Classic,
+    Puzzle,
+}
+
 #[derive(Default, Debug)]
 enum GameState {
     #[default]
@@ -241,19 +400,55 @@ enum GameState {
 
 #[derive(Debug, Default)]
 struct App {
-    board_widget: BoardWidget,
-    player_widget: PlayerWidget,
     block_placement_widget: BlockPlacementWidget,
     game_state: GameState,
+    game_mode: GameMode,
     player_selection_list: PlayerSelectionList,
+    assist: bool,
+    /// row and column indexes drawn in gutters around the board
+    coordinates: bool,
+    /// whether each block of the current human player can be placed
+    placeable_pieces: Vec<bool>,
+    /// a human move was played and can still be undone
+    reviewing_move: bool,
+    grid_lines: bool,
+    /// show each player placed cells as a bar below the board
+    piece_bars: bool,
+    scoring: ScoringConfig,
+    theme: Theme,
+    /// when and where the last block was placed
+    last_placed: Option<(Instant, Vec<(usize, usize)>)>,
+    flash_duration: Duration,
+    /// computer move being revealed cell by cell and when it was played
+    revealing: Option<(Instant, Vec<(usize, usize)>)>,
+    /// where the board was last rendered, to locate mouse events
+    board_area: Rect,
+    /// transient message and when it was posted
+    notice: Option<(Instant, String)>,
+    /// text description of the board shown instead of the status line, for screen readers
+    description: Option<String>,
+    /// one line per move played, see [`move_line`]
+    move_lines: Vec<String>,
+    /// moves scrolled back from the latest in the move log
+    move_log_scroll: usize,
+    /// lines of the move log at the last render
+    move_log_height: usize,
 }
 
-impl Widget for &mut App {
+/// App rendered along with the game state it borrows.
+struct AppView<'a> {
+    app: &'a mut App,
+    board: &'a Board,
+    player: Option<&'a Player>,
+    players: &'a [Player],
+}
+
+impl Widget for AppView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        match self.game_state {
+        match self.app.game_state {
             GameState::MainMenu => {
                 let [header, menu, footer] =
-                    Layout::vertical([Length(2), Fill(1), Length(1)]).areas(area);
+                    Layout::vertical([Length(2), Fill(1), Length(2)]).areas(area);
 
                 let block = ratatui::widgets::Block::new()
                     .title(Line::raw("Player Selection").centered())
@@ -261,6 +456,7 @@ impl Widget for &mut App {
                     .border_set(symbols::border::ROUNDED);
 
                 let items: Vec<ListItem> = self
+                    .app
                     .player_selection_list
                     .items
                     .iter()
@@ -272,48 +468,841 @@ impl Widget for &mut App {
                     .highlight_spacing(HighlightSpacing::Always);
 
                 Paragraph::new("Blockus-rs").centered().render(header, buf);
-                StatefulWidget::render(list, menu, buf, &mut self.player_selection_list.state);
-                Paragraph::new("Use ↓↑ to move, ←→ to change Computer<->Human<->Not selected, enter to start game")
-                    .centered()
-                    .render(footer, buf);
+                StatefulWidget::render(list, menu, buf, &mut self.app.player_selection_list.state);
+                let help = if self.app.player_selection_list.editing_name {
+                    "Type a name, enter to confirm"
+                } else {
+                    "Use ↓↑ to move, ←→ to change Computer<->Human<->Not selected, tab to name a human player or pick a computer preset, enter to start game"
+                };
+                let text = match &self.app.notice {
+                    Some((posted_at, notice))
+                        if should_flash(posted_at.elapsed(), NOTICE_DURATION) =>
+                    {
+                        format!("{}\n{}", notice, help)
+                    }
+                    _ => String::from(help),
+                };
+                Paragraph::new(text).centered().render(footer, buf);
+            }
+            GameState::Game if terminal_too_small(area) => {
+                Paragraph::new(format!(
+                    "terminal too small (need ≥ {}×{})",
+                    MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+                ))
+                .centered()
+                .render(area, buf);
             }
             GameState::Game => {
-                let [top, bottom] = Layout::vertical([Length(20), Min(0)]).areas(area);
-                let [board, player] = Layout::horizontal([Length(40), Min(40)]).areas(top);
-                self.board_widget.render(board, buf);
-                self.block_placement_widget.render(board, buf);
-                self.player_widget.render(player, buf);
-                let text = if let Some(block) = &self.block_placement_widget.block_placement {
+                let (board_width, board_height) =
+                    board_region_size(self.board.nrows(), self.board.ncols());
+                let (board_width, board_height) = if self.app.coordinates {
+                    (
+                        board_width.saturating_add(ROW_LABEL_WIDTH),
+                        board_height.saturating_add(1),
+                    )
+                } else {
+                    (board_width, board_height)
+                };
+                let [top, bottom] =
+                    Layout::vertical([Length(board_height.max(MIN_HAND_HEIGHT)), Min(0)])
+                        .areas(area);
+                let [board, player] = Layout::horizontal([Length(board_width), Min(40)]).areas(top);
+                // vertically centered next to the hand
+                let [board] = Layout::vertical([Length(board_height)])
+                    .flex(Flex::Center)
+                    .areas(board);
+                let bars_height = if self.app.piece_bars {
+                    self.players.len() as u16
+                } else {
+                    0
+                };
+                let [bars, bottom] = Layout::vertical([Length(bars_height), Min(0)]).areas(bottom);
+                PieceBarsWidget {
+                    board: self.board,
+                    players: self.players,
+                    theme: &self.app.theme,
+                }
+                .render(bars, buf);
+                let board = if self.app.coordinates {
+                    let (cells, labels) =
+                        coordinate_gutters(board, self.board.nrows(), self.board.ncols());
+                    for (x, y, label) in labels {
+                        buf.set_string(x, y, label, Style::default().fg(Color::DarkGray));
+                    }
+                    cells
+                } else {
+                    board
+                };
+                self.app.board_area = board;
+                let highlighted_cells = match &self.app.last_placed {
+                    Some((placed_at, cells))
+                        if should_flash(placed_at.elapsed(), self.app.flash_duration) =>
+                    {
+                        cells.as_slice()
+                    }
+                    _ => &[],
+                };
+                let hidden_cells: Vec<(usize, usize)> = match &self.app.revealing {
+                    Some((placed_at, cells)) => cells
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| {
+                            !should_reveal_cell(*index, placed_at.elapsed(), cells.len())
+                        })
+                        .map(|(_, cell)| *cell)
+                        .collect(),
+                    None => vec![],
+                };
+                BoardWidget {
+                    board: self.board,
+                    grid_lines: self.app.grid_lines,
+                    highlighted_cells,
+                    hidden_cells: &hidden_cells,
+                    theme: &self.app.theme,
+                }
+                .render(board, buf);
+                self.app.block_placement_widget.render(board, buf);
+                if let Some(player_ref) = self.player {
+                    PlayerWidget {
+                        player: player_ref,
+                        placeable: &self.app.placeable_pieces,
+                        theme: &self.app.theme,
+                    }
+                    .render(player, buf);
+                }
+                let text = if self.app.reviewing_move {
+                    String::from("u(ndo) last move, enter to end turn")
+                } else if let Some(block) = &self.app.block_placement_widget.block_placement {
+                    let piece = OrientedPiece::new(&block.block);
+                    let orientation = piece.distinct_position(OrientedPiece::index(
+                        block.rotation,
+                        block.transposition,
+                    ));
                     format!(
-                        "row: {}, col: {}, q(uit) j/k (previous/next) r(otate) t(ranspose)",
-                        block.row, block.col
+                        "piece: {}, orientation: {}/{}, row: {}, col: {}, q (drop piece) g(ive up) j/k (previous/next) r(otate) t(ranspose) f(lip) tab (orientation) a(ssist: {}) c(oordinates) d(escribe) [/] (move log)",
+                        block
+                            .block
+                            .standard_name()
+                            .map_or(String::from("custom"), |name| name.to_string()),
+                        orientation + 1,
+                        piece.distinct.len(),
+                        block.row,
+                        block.col,
+                        if self.app.assist { "on" } else { "off" }
                     )
+                } else if self.player.is_some_and(|player| player.human) {
+                    String::from("j/k (pick a piece) g(ive up) q(uit)")
                 } else {
                     String::from("q(uit)")
                 };
-                Text::from(text).left_aligned().render(bottom, buf);
+                let text = format!(
+                    "{}\nboard filled: {:.0}%",
+                    text,
+                    self.board.fill_ratio() * 100.0
+                );
+                let text = match self.player {
+                    Some(player) => {
+                        format!(
+                            "{}, player: {}, score: {}, open corners: {}",
+                            text,
+                            player.display_name(),
+                            self.board.score_estimate(player, &self.app.scoring),
+                            self.board.open_corner_count(player.player_id)
+                        )
+                    }
+                    None => text,
+                };
+                let text = if self.app.game_mode == GameMode::Puzzle {
+                    format!("{}\ncells placed: {}", text, self.board.count_cells(1))
+                } else {
+                    text
+                };
+                let text = match &self.app.notice {
+                    Some((posted_at, notice))
+                        if should_flash(posted_at.elapsed(), NOTICE_DURATION) =>
+                    {
+                        format!("{}\n{}", notice, text)
+                    }
+                    _ => text,
+                };
+                let bottom = if area.width >= MIN_TERMINAL_WIDTH + MOVE_LOG_WIDTH {
+                    let [bottom, move_log] =
+                        Layout::horizontal([Min(0), Length(MOVE_LOG_WIDTH)]).areas(bottom);
+                    self.app.move_log_height = move_log.height as usize;
+                    let visible = visible_moves(
+                        self.app.move_lines.len(),
+                        move_log.height as usize,
+                        self.app.move_log_scroll,
+                    );
+                    Text::from_iter(self.app.move_lines[visible].iter().map(String::as_str))
+                        .left_aligned()
+                        .render(move_log, buf);
+                    bottom
+                } else {
+                    bottom
+                };
+                match &self.app.description {
+                    Some(description) => Paragraph::new(description.as_str())
+                        .wrap(Wrap { trim: true })
+                        .render(bottom, buf),
+                    None => Text::from(text).left_aligned().render(bottom, buf),
+                }
+            }
+        }
+    }
+}
+
+/// Placed cells of each player as a bar, one line per player.
+struct PieceBarsWidget<'a> {
+    board: &'a Board,
+    players: &'a [Player],
+    theme: &'a Theme,
+}
+
+const PIECE_BAR_WIDTH: usize = 20;
+
+/// Length of the filled part of a `width` long bar for `placed` cells out of `total`.
+fn bar_length(placed: usize, total: usize, width: usize) -> usize {
+    (placed.min(total) * width).checked_div(total).unwrap_or(0)
+}
+
+impl Widget for PieceBarsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for (player, y) in self.players.iter().zip(area.top()..area.bottom()) {
+            let placed = self.board.count_cells(player.player_id);
+            let total = placed + player.remaining_cells();
+            let filled = bar_length(placed, total, PIECE_BAR_WIDTH);
+            let line = Line::from(vec![
+                Span::raw(format!("{} ", player.display_name())),
+                Span::styled(
+                    "█".repeat(filled),
+                    Style::default().fg(self.theme.color(player.player_id)),
+                ),
+                Span::raw("░".repeat(PIECE_BAR_WIDTH - filled)),
+                Span::raw(format!(
+                    " {}/{}, corners: {}",
+                    placed,
+                    total,
+                    self.board.open_corner_count(player.player_id)
+                )),
+            ]);
+            buf.set_line(area.left(), y, &line, area.width);
+        }
+    }
+}
+
+/// Rows of the hand area, taller boards push the status line down.
+const MIN_HAND_HEIGHT: u16 = 20;
+
+/// `(width, height)` of a `nrows` x `ncols` board on screen, cells being two columns wide.
+fn board_region_size(nrows: usize, ncols: usize) -> (u16, u16) {
+    let to_u16 = |n: usize| u16::try_from(n).unwrap_or(u16::MAX);
+    (to_u16(ncols.saturating_mul(2)), to_u16(nrows))
+}
+
+/// Columns of the move log next to the status line, shown on terminals wide enough for both.
+const MOVE_LOG_WIDTH: u16 = 32;
+
+/// Move log line of `player_id` placing `block_placement`, `None` being a pass,
+/// e.g. `"P2: Y-pentomino @ (3,7) r2"`, `t` marking a transposed piece.
+fn move_line(player_id: u8, block_placement: Option<&BlockPlacement>) -> String {
+    match block_placement {
+        Some(block_placement) => format!(
+            "P{}: {} @ ({},{}) r{}{}",
+            player_id,
+            block_placement
+                .block
+                .standard_name()
+                .map_or(String::from("custom piece"), |name| name.long_name()),
+            block_placement.row,
+            block_placement.col,
+            block_placement.rotation,
+            if block_placement.transposition == 1 {
+                "t"
+            } else {
+                ""
             }
+        ),
+        None => format!("P{}: pass", player_id),
+    }
+}
+
+/// Indexes of the moves shown in a `height` lines move log, `scroll` moves back from the
+/// latest but always filling the log when there are enough moves.
+fn visible_moves(move_count: usize, height: usize, scroll: usize) -> std::ops::Range<usize> {
+    let end = move_count
+        .saturating_sub(scroll)
+        .max(height.min(move_count));
+    end.saturating_sub(height)..end
+}
+
+/// Move log `scroll` after `action`, one move back or forward for the scroll actions, at
+/// most back to the `height` lines showing the first moves.
+fn scroll_move_log(scroll: usize, action: Action, move_count: usize, height: usize) -> usize {
+    match action {
+        Action::ScrollLogUp => (scroll + 1).min(move_count.saturating_sub(height)),
+        Action::ScrollLogDown => scroll.saturating_sub(1),
+        _ => scroll,
+    }
+}
+
+/// Columns left of the board for the row indexes, the top gutter is one row high.
+const ROW_LABEL_WIDTH: u16 = 3;
+
+/// Board cells area and labels of a `nrows` x `ncols` board drawn with its coordinates in
+/// `area`: right-aligned row indexes in the left gutter and the last digit of the column
+/// indexes in the top gutter.
+///
+/// Labels are `(x, y, text)`, those not fitting in `area` are left out.
+fn coordinate_gutters(area: Rect, nrows: usize, ncols: usize) -> (Rect, Vec<(u16, u16, String)>) {
+    let cells = Rect::new(
+        area.x.saturating_add(ROW_LABEL_WIDTH),
+        area.y.saturating_add(1),
+        area.width.saturating_sub(ROW_LABEL_WIDTH),
+        area.height.saturating_sub(1),
+    )
+    .intersection(area);
+    let row_labels = (0..nrows)
+        .map(|row| (area.x, cells.y as usize + row, format!("{:>2}", row)))
+        .filter(|&(_, y, _)| y < cells.bottom() as usize);
+    let col_labels = (0..ncols)
+        .map(|col| (cells.x as usize + 2 * col, area.y, (col % 10).to_string()))
+        .filter(|&(x, _, _)| x < cells.right() as usize);
+    let labels = row_labels
+        .map(|(x, y, label)| (x, y as u16, label))
+        .chain(col_labels.map(|(x, y, label)| (x as u16, y, label)))
+        .collect();
+    (cells, labels)
+}
+
+/// Whether cell `index` of the `cell_count` cells of a computer move is shown `elapsed`
+/// after the move, cells appearing at regular intervals over [`REVEAL_DURATION`].
+fn should_reveal_cell(index: usize, elapsed: Duration, cell_count: usize) -> bool {
+    // cell `index` appears `index / cell_count` of the way through
+    elapsed.as_secs_f64() * cell_count as f64 >= REVEAL_DURATION.as_secs_f64() * index as f64
+}
+
+/// Whether the last placed block should still be highlighted.
+fn should_flash(elapsed: Duration, flash_duration: Duration) -> bool {
+    elapsed < flash_duration
+}
+
+/// Players of a quick start game: a human player 1 then computer players.
+fn quick_start_players(player_count: u8, block_set: &[Block]) -> Vec<Player> {
+    (1..=player_count)
+        .map(|player_id| Player {
+            player_id,
+            human: player_id == 1,
+            blocks: block_set.to_vec(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Players chosen in the main menu, empty if every row is "Not selected".
+fn selected_players(items: &[PlayerSelectionItem], block_set: &[Block]) -> Vec<Player> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(player_id, player_selection)| match player_selection.status {
+                PlayerSelectionStatus::Computer => Some(Player {
+                    player_id: (player_id + 1) as u8,
+                    human: false,
+                    blocks: block_set.to_vec(),
+                    name: player_selection.name.clone(),
+                    ..Default::default()
+                }),
+                PlayerSelectionStatus::Human => Some(Player {
+                    player_id: (player_id + 1) as u8,
+                    human: true,
+                    blocks: block_set.to_vec(),
+                    name: player_selection.name.clone(),
+                    ..Default::default()
+                }),
+                PlayerSelectionStatus::NotSelected => None,
+            },
+        )
+        .collect()
+}
+
+/// Where a human turn is at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum HumanTurn {
+    /// no piece held, cycling picks one
+    Selecting,
+    /// a piece is held over the board
+    Placing,
+    /// quitting again ends the session
+    ConfirmingQuit,
+    /// the player resigned and is out of the game, the others keep playing
+    Resigned,
+    /// the whole session ends
+    Quitting,
+}
+
+/// Human turn after `action`.
+///
+/// Quitting first drops the held piece, then asks for confirmation before ending the session.
+fn next_human_turn(turn: HumanTurn, action: Action) -> HumanTurn {
+    match (turn, action) {
+        (HumanTurn::Resigned | HumanTurn::Quitting, _) => turn,
+        (_, Action::Resign) => HumanTurn::Resigned,
+        (HumanTurn::Placing, Action::Quit) => HumanTurn::Selecting,
+        (HumanTurn::Selecting, Action::Quit) => HumanTurn::ConfirmingQuit,
+        (HumanTurn::ConfirmingQuit, Action::Quit) => HumanTurn::Quitting,
+        (
+            HumanTurn::Selecting | HumanTurn::ConfirmingQuit,
+            Action::CyclePrev | Action::CycleNext | Action::Suggest,
+        ) => HumanTurn::Placing,
+        (HumanTurn::Selecting | HumanTurn::ConfirmingQuit, _) => HumanTurn::Selecting,
+        (HumanTurn::Placing, _) => HumanTurn::Placing,
+    }
+}
+
+/// Whether `player` has to pass: a human without legal moves, which ends a puzzle.
+///
+/// Computer players pass on their own, see [`Strategy::place`].
+fn must_pass(board: &Board, player: &Player, first_block: bool) -> bool {
+    player.human && !board.has_any_legal_placement(player, first_block)
+}
+
+/// Board and hand, if any, of a `--puzzle` file: a [`PuzzleFile`] if `ron`, else the
+/// board alone as accepted by [`Board::from_str`].
+fn parse_puzzle(
+    contents: &str,
+    ron: bool,
+) -> std::result::Result<(Board, Option<Vec<Block>>), String> {
+    if !ron {
+        let board = Board::from_str(contents.trim_end()).map_err(|e| e.to_string())?;
+        return Ok((board, None));
+    }
+    let puzzle = PuzzleFile::from_ron(contents).map_err(|e| e.to_string())?;
+    let board = Board::from_str(puzzle.board.trim_end()).map_err(|e| e.to_string())?;
+    let pieces = puzzle
+        .pieces
+        .map(|pieces| Block::set_from_str(&pieces))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    Ok((board, pieces))
+}
+
+/// Whether every player is eliminated, ending the game.
+fn all_eliminated(players: &[Player], players_eliminated: &HashSet<u8>) -> bool {
+    players
+        .iter()
+        .all(|player| players_eliminated.contains(&player.player_id))
+}
+
+/// Message announcing that `player` was just eliminated.
+fn elimination_notice(board: &Board, player: &Player, first_block: bool) -> String {
+    if board.has_any_legal_placement(player, first_block) {
+        format!("{} eliminated", player.display_name())
+    } else {
+        format!("{} eliminated: no legal moves", player.display_name())
+    }
+}
+
+/// Opponents of `player_id` still in the game left without legal moves once it plays
+/// `placement`, see [`Board::leaves_player_stuck`].
+fn players_left_stuck<'a>(
+    board: &Board,
+    placement: &BlockPlacement,
+    player_id: u8,
+    players: &'a [Player],
+    players_eliminated: &HashSet<u8>,
+) -> Vec<&'a Player> {
+    players
+        .iter()
+        .filter(|p| p.player_id != player_id && !players_eliminated.contains(&p.player_id))
+        // not those already stuck before the move
+        .filter(|p| board.has_any_legal_placement(p, board.count_cells(p.player_id) == 0))
+        .filter(|p| board.leaves_player_stuck(placement, player_id, p))
+        .collect()
+}
+
+/// Board `(row, col)` under the terminal `(column, row)` cursor, board cells being
+/// two terminal columns wide, `None` if the cursor is out of `board_area`.
+///
+/// Cursors in the area but past the board cells snap to the nearest edge cell.
+fn cursor_to_anchor(
+    cursor: (u16, u16),
+    board_area: Rect,
+    nrows: usize,
+    ncols: usize,
+) -> Option<(usize, usize)> {
+    let (column, row) = cursor;
+    if !(board_area.left()..board_area.right()).contains(&column)
+        || !(board_area.top()..board_area.bottom()).contains(&row)
+    {
+        return None;
+    }
+    let col = usize::from((column - board_area.left()) / 2);
+    let row = usize::from(row - board_area.top());
+    Some((
+        row.min(nrows.saturating_sub(1)),
+        col.min(ncols.saturating_sub(1)),
+    ))
+}
+
+/// Nearest anchor to `target` (Manhattan distance) among `candidates`.
+///
+/// Ties are broken by the candidates order.
+fn nearest_anchor(candidates: &[(usize, usize)], target: (usize, usize)) -> Option<(usize, usize)> {
+    candidates
+        .iter()
+        .min_by_key(|(row, col)| row.abs_diff(target.0) + col.abs_diff(target.1))
+        .cloned()
+}
+
+/// Move the anchor of `block_placement` so that its oriented block stays on a
+/// `nrows` x `ncols` board.
+fn clamp_anchor(block_placement: &mut BlockPlacement, nrows: usize, ncols: usize) {
+    let (_, _, block) = block_placement.as_row_col_block();
+    block_placement.row = block_placement.row.min(nrows.saturating_sub(block.nrows()));
+    block_placement.col = block_placement.col.min(ncols.saturating_sub(block.ncols()));
+}
+
+/// Move the anchor of `block_placement` by `(drow, dcol)` cells, keeping its oriented block
+/// on a `nrows` x `ncols` board: the next move starts from where this one left the block.
+fn move_anchor(
+    block_placement: &mut BlockPlacement,
+    (drow, dcol): (i32, i32),
+    nrows: usize,
+    ncols: usize,
+) {
+    block_placement.row = (block_placement.row as i32 + drow).max(0) as usize;
+    block_placement.col = (block_placement.col as i32 + dcol).max(0) as usize;
+    clamp_anchor(block_placement, nrows, ncols);
+}
+
+/// Scoring bonuses from `<all placed bonus>,<monomino last bonus>`, e.g. `15,0`.
+fn parse_scoring(s: &str) -> std::result::Result<ScoringConfig, String> {
+    let invalid = || format!("invalid scoring {}, expected e.g. 15,5", s);
+    let (all_placed_bonus, monomino_last_bonus) = s.split_once(',').ok_or_else(invalid)?;
+    Ok(ScoringConfig {
+        all_placed_bonus: all_placed_bonus.trim().parse().map_err(|_| invalid())?,
+        monomino_last_bonus: monomino_last_bonus.trim().parse().map_err(|_| invalid())?,
+        ..Default::default()
+    })
+}
+
+/// Turn budget and penalty per second over it from `<seconds>,<penalty>`, e.g. `10,1`.
+fn parse_overtime(s: &str) -> std::result::Result<(Duration, i32), String> {
+    let invalid = || format!("invalid overtime {}, expected e.g. 10,1", s);
+    let (turn_budget, overtime_penalty) = s.split_once(',').ok_or_else(invalid)?;
+    Ok((
+        Duration::from_secs(turn_budget.trim().parse().map_err(|_| invalid())?),
+        overtime_penalty.trim().parse().map_err(|_| invalid())?,
+    ))
+}
+
+/// `Color::Rgb` from a `RRGGBB` hex string, e.g. `ff8000`.
+fn parse_hex_color(s: &str) -> std::result::Result<(u8, u8, u8), String> {
+    let invalid = || format!("invalid color {}, expected RRGGBB e.g. ff8000", s);
+    if s.len() != 6 || !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let component = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| invalid());
+    Ok((component(0)?, component(2)?, component(4)?))
+}
+
+/// Default theme with the player colors from the command line flags, falling back on the
+/// `config_file` values.
+fn theme_config(args: &[String], config_file: &ConfigFile) -> std::result::Result<Theme, String> {
+    let mut theme = Theme::default();
+    for player_id in 1..=4 {
+        if let Some(hex) =
+            arg_value(args, &format!("--color{}", player_id)).or(config_file.colors.get(&player_id))
+        {
+            theme.player_colors[usize::from(player_id) - 1] = parse_hex_color(hex)?;
+        }
+    }
+    Ok(theme)
+}
+
+/// Game settings from the command line flags, falling back on the `config_file` values.
+fn game_config(
+    args: &[String],
+    config_file: &ConfigFile,
+) -> std::result::Result<GameConfig, String> {
+    let mut scoring = match arg_value(args, "--scoring").or(config_file.scoring.as_ref()) {
+        Some(scoring) => parse_scoring(scoring)?,
+        None => ScoringConfig::default(),
+    };
+    if let Some(overtime) = arg_value(args, "--overtime").or(config_file.overtime.as_ref()) {
+        let (turn_budget, overtime_penalty) = parse_overtime(overtime)?;
+        scoring.turn_budget = Some(turn_budget);
+        scoring.overtime_penalty = overtime_penalty;
+    }
+    match arg_value(args, "--contiguity-bonus") {
+        Some(bonus) => {
+            scoring.contiguity_bonus = bonus
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid contiguity bonus {}", bonus))?;
         }
+        None => scoring.contiguity_bonus = config_file.contiguity_bonus.unwrap_or(0),
     }
+    Ok(GameConfig {
+        turn_order: match arg_value(args, "--turn-order") {
+            Some(turn_order) => turn_order
+                .split(',')
+                .map(|player_id| player_id.trim().parse())
+                .collect::<std::result::Result<Vec<u8>, _>>()
+                .map_err(|e| format!("invalid turn order {}: {}", turn_order, e))?,
+            None => config_file.turn_order.clone(),
+        },
+        scoring,
+    })
+}
+
+/// Value following `flag` in the command line arguments.
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
 }
 
 fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let config_file = match arg_value(&args, "--config") {
+        Some(path) => ConfigFile::from_ron(&std::fs::read_to_string(path)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        None => ConfigFile::default(),
+    };
+    let puzzle_path = arg_value(&args, "--puzzle");
+    let seed: u64 = match arg_value(&args, "--seed") {
+        Some(seed) => seed
+            .parse()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+        None => config_file.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        }),
+    };
+    let strategy_name = arg_value(&args, "--strategy")
+        .or(config_file.strategy.as_ref())
+        .map_or("greedy", |name| name.as_str());
+    if strategy_from_name(strategy_name, seed).is_none() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unknown strategy {}", strategy_name),
+        ));
+    }
+
+    let keymap = match arg_value(&args, "--keymap") {
+        Some(path) => KeyMap::from_ron(&std::fs::read_to_string(path)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        None => KeyMap::from_bindings(config_file.keymap.clone())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+    };
+
+    let quick_players: Option<u8> = match arg_value(&args, "--quick") {
+        Some(player_count) => match player_count.parse() {
+            Ok(player_count @ 1..=4) => Some(player_count),
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("invalid player count {}, expected 1 to 4", player_count),
+                ))
+            }
+        },
+        None => None,
+    };
+    let game_config = game_config(&args, &config_file)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let theme = theme_config(&args, &config_file)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let block_set = match arg_value(&args, "--pieces") {
+        Some(path) => Block::set_from_file(path)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
+        None => Block::default_block_set(),
+    };
+
+    let mut puzzle_pieces = None;
+    let mut board = match puzzle_path {
+        Some(path) => {
+            let (board, pieces) =
+                parse_puzzle(&std::fs::read_to_string(path)?, path.ends_with(".ron"))
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            puzzle_pieces = pieces;
+            board
+        }
+        None => {
+            let (nrows, ncols) = config_file.board_size.unwrap_or((20, 20));
+            if nrows == 0 || ncols == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid board size {}x{}", nrows, ncols),
+                ));
+            }
+            Board::new(nrows, ncols)
+        }
+    };
+    if args.iter().any(|arg| arg == "--torus") || config_file.torus {
+        board.set_topology(Topology::Torus);
+    }
+    if args.iter().any(|arg| arg == "--free-start") || config_file.free_start {
+        board.set_ruleset(PlacementRuleset {
+            first_must_cover_start: false,
+            ..Default::default()
+        });
+    }
+
+    #[cfg(feature = "net")]
+    if let Some(addr) = arg_value(&args, "--serve") {
+        println!("{}", net::serve(addr.as_str(), board)?);
+        return Ok(());
+    }
+    #[cfg(feature = "net")]
+    if let Some(addr) = arg_value(&args, "--connect") {
+        let strategy = strategy_from_name(strategy_name, seed).expect("strategy checked above");
+        println!(
+            "{}",
+            net::play_remote(net::connect(addr.as_str())?, strategy)?
+        );
+        return Ok(());
+    }
+
+    if args.iter().any(|arg| arg == "--simulate") {
+        let games: usize = match arg_value(&args, "--games") {
+            Some(games) => games
+                .parse()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+            None => 10,
+        };
+        let strategies = arg_value(&args, "--strategies").map_or("greedy,random", |s| s.as_str());
+        let strategy_names: Vec<&str> = strategies.split(',').map(str::trim).collect();
+        let summary = simulate(
+            &board,
+            games,
+            seed,
+            &strategy_names,
+            &block_set,
+            &game_config.scoring,
+        )
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid strategies {}, expected 1 to 4 known names",
+                    strategies
+                ),
+            )
+        })?;
+        println!("{}", serde_json::to_string(&summary)?);
+        return Ok(());
+    }
+
+    if args.iter().any(|arg| arg == "--demo") {
+        let (players, moves) = demo_game(&mut board, 4, &block_set, strategy_name, seed)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} played an illegal move", strategy_name),
+                )
+            })?;
+        if args.iter().any(|arg| arg == "--commentary") {
+            let commentary = move_commentary(&board, &players, &moves);
+            for (line, (delta, label)) in move_log(&moves).lines().zip(commentary) {
+                println!("{} ({:+}, {})", line, delta, label);
+            }
+        } else {
+            print!("{}", move_log(&moves));
+        }
+        println!("{}", board.print_with_coordinates());
+        for player in players {
+            println!(
+                "player: {}. left: {}. score: {}",
+                player.player_id,
+                player.remaining_cells(),
+                board.score_estimate(&player, &game_config.scoring)
+            );
+        }
+        println!("seed: {} (replay with --demo --seed {})", seed, seed);
+        return Ok(());
+    }
+
+    let mut app = App {
+        grid_lines: args.iter().any(|arg| arg == "--grid-lines") || config_file.grid_lines,
+        piece_bars: args.iter().any(|arg| arg == "--bars") || config_file.bars,
+        scoring: game_config.scoring,
+        theme,
+        flash_duration: FLASH_DURATION,
+        game_mode: if puzzle_path.is_some() {
+            GameMode::Puzzle
+        } else {
+            GameMode::Classic
+        },
+        ..Default::default()
+    };
+    for (item, setup) in app
+        .player_selection_list
+        .items
+        .iter_mut()
+        .zip(&config_file.players)
+    {
+        item.status = match setup {
+            PlayerSetup::Computer => PlayerSelectionStatus::Computer,
+            PlayerSetup::Human(_) => PlayerSelectionStatus::Human,
+            PlayerSetup::NotSelected => PlayerSelectionStatus::NotSelected,
+        };
+        if let PlayerSetup::Human(name) = setup {
+            if !name.trim().is_empty() {
+                item.name = name.chars().take(MAX_NAME_LENGTH).collect();
+            }
+        }
+    }
+
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     enable_raw_mode()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
-    let mut app = App::default();
-
-    // main menu
-    loop {
+    // main menu, puzzles and quick starts skip it
+    while app.game_mode == GameMode::Classic && quick_players.is_none() {
         terminal.draw(|frame| {
             let area = frame.size();
-            frame.render_widget(&mut app, area);
+            let app_view = AppView {
+                app: &mut app,
+                board: &board,
+                player: None,
+                players: &[],
+            };
+            frame.render_widget(app_view, area);
         })?;
 
         if event::poll(Duration::from_millis(16))? {
-            if let event::Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if needs_full_redraw(std::slice::from_ref(&event)) {
+                terminal.clear()?;
+            }
+            if let event::Event::Key(key) = event {
+                let list = &mut app.player_selection_list;
+                if key.kind == KeyEventKind::Press && list.editing_name {
+                    if let Some(i) = list.state.selected() {
+                        list.editing_name = edit_name(&mut list.items[i].name, key.code);
+                        if !list.editing_name && list.items[i].name.trim().is_empty() {
+                            list.items[i].name = Player::default_name(list.items[i].player_id);
+                        }
+                    }
+                    continue;
+                }
+                if key.kind == KeyEventKind::Press && key.code == KeyCode::Tab {
+                    if let Some(i) = list.state.selected() {
+                        match list.items[i].status {
+                            PlayerSelectionStatus::Human => list.editing_name = true,
+                            PlayerSelectionStatus::Computer => {
+                                list.items[i].personality =
+                                    next_personality(list.items[i].personality);
+                            }
+                            PlayerSelectionStatus::NotSelected => {}
+                        }
+                    }
+                }
                 if key.kind == KeyEventKind::Press && key.code == KeyCode::Up {
                     app.player_selection_list.state.select_previous();
                 }
@@ -345,99 +1334,159 @@ fn main() -> Result<()> {
                     }
                 }
                 if key.kind == KeyEventKind::Press && key.code == KeyCode::Enter {
-                    break;
+                    // without any player the game loop would never end
+                    if selected_players(&app.player_selection_list.items, &block_set).is_empty() {
+                        let notice = String::from("select at least one player");
+                        app.notice = Some((Instant::now(), notice));
+                    } else {
+                        break;
+                    }
                 }
             }
         }
     }
     app.game_state = GameState::Game;
 
-    let mut players: Vec<Player> = app
-        .player_selection_list
-        .items
+    let mut players: Vec<Player> = if app.game_mode == GameMode::Puzzle {
+        vec![Player {
+            player_id: 1,
+            human: true,
+            blocks: puzzle_pieces.unwrap_or_else(|| block_set.clone()),
+            ..Default::default()
+        }]
+    } else if let Some(player_count) = quick_players {
+        quick_start_players(player_count, &block_set)
+    } else {
+        selected_players(&app.player_selection_list.items, &block_set)
+    };
+    let players_id: Vec<u8> = game_config.play_order(&players);
+    // judges human moves with the computer players strategy
+    let mut coach = if args.iter().any(|arg| arg == "--coach") {
+        strategy_from_name(strategy_name, seed)
+    } else {
+        None
+    };
+    let mut strategies: HashMap<u8, Box<dyn Strategy>> = players
         .iter()
-        .enumerate()
-        .filter_map(
-            |(player_id, player_selection)| match player_selection.status {
-                PlayerSelectionStatus::Computer => Some(Player {
-                    player_id: (player_id + 1) as u8,
-                    human: false,
-                    blocks: Block::default_block_set(),
-                }),
-                PlayerSelectionStatus::Human => Some(Player {
-                    player_id: (player_id + 1) as u8,
-                    human: true,
-                    blocks: Block::default_block_set(),
-                }),
-                PlayerSelectionStatus::NotSelected => None,
-            },
-        )
+        .filter(|p| !p.human)
+        .filter_map(|p| {
+            computer_strategy(
+                &app.player_selection_list.items,
+                p.player_id,
+                strategy_name,
+                seed.wrapping_add(p.player_id as u64),
+            )
+            .map(|s| (p.player_id, s))
+        })
         .collect();
-    let players_id: Vec<u8> = players.iter().map(|p| p.player_id).collect();
-
-    let mut board = Board::new(20, 20);
 
     let mut turn_counter: usize = 0;
     let mut players_eliminated = HashSet::<u8>::new();
 
-    loop {
+    'game: loop {
         for &player_id in players_id.iter() {
-            app.board_widget.board = board.clone();
-
             if let Some(position) = players.iter().position(|p| p.player_id == player_id) {
-                let player: &Player = players.get(position).unwrap();
                 if players_eliminated.contains(&player_id) {
                     continue;
                 }
-                app.player_widget.player = player.clone();
-                app.block_placement_widget.player_id = player.player_id;
-                let block_placement: Option<BlockPlacement> = if player.human {
-                    if let Some(first_block) = player.blocks.first() {
-                        let mut block_selection: usize = 0;
-                        let mut player_block_placement = Some(BlockPlacement {
-                            block: first_block.clone(),
-                            row: 0,
-                            col: 0,
-                            rotation: 0,
-                            transposition: 0,
-                        });
-                        loop {
-                            if event::poll(Duration::from_millis(16))? {
-                                if let event::Event::Key(key) = event::read()? {
-                                    if key.kind == KeyEventKind::Press
-                                        && key.code == KeyCode::Char('q')
+                // a human turn is played again if its move is undone
+                loop {
+                    let player: &Player = players.get(position).unwrap();
+                    app.block_placement_widget.color = app.theme.color(player.player_id);
+                    let turn_start = Instant::now();
+                    let mut rendering_time = Duration::ZERO;
+                    let mut resigned = false;
+                    let block_placement: Option<BlockPlacement> = if must_pass(
+                        &board,
+                        player,
+                        turn_counter == 0,
+                    ) {
+                        None
+                    } else if player.human {
+                        if let Some(first_block) = player.blocks.first() {
+                            app.placeable_pieces =
+                                board.placeable_pieces(player, turn_counter == 0);
+                            let mut block_selection: usize = 0;
+                            let mut player_block_placement = Some(BlockPlacement {
+                                block: first_block.clone(),
+                                row: 0,
+                                col: 0,
+                                rotation: 0,
+                                transposition: 0,
+                            });
+                            let mut turn = HumanTurn::Placing;
+                            // placement confirmed once despite leaving opponents stuck
+                            let mut stuck_warning: Option<BlockPlacement> = None;
+                            'placement: loop {
+                                let events = drain_events(Duration::from_millis(16))?;
+                                if needs_full_redraw(&events) {
+                                    terminal.clear()?;
+                                }
+                                for input in inputs(&keymap, &events) {
+                                    let action = match input {
+                                        Input::Action(action) => action,
+                                        Input::Pointer { column, row, click } => {
+                                            let Some(block) = &mut player_block_placement else {
+                                                continue;
+                                            };
+                                            let Some(anchor) = cursor_to_anchor(
+                                                (column, row),
+                                                app.board_area,
+                                                board.nrows(),
+                                                board.ncols(),
+                                            ) else {
+                                                continue;
+                                            };
+                                            (block.row, block.col) = anchor;
+                                            if !click {
+                                                continue;
+                                            }
+                                            // click to place
+                                            Action::Confirm
+                                        }
+                                    };
+                                    if let (Some((drow, dcol)), Some(block)) =
+                                        (action.movement(), &mut player_block_placement)
                                     {
-                                        player_block_placement = None;
-                                        break;
+                                        move_anchor(
+                                            block,
+                                            (drow, dcol),
+                                            board.nrows(),
+                                            board.ncols(),
+                                        );
                                     }
-                                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Left
-                                    {
-                                        if let Some(block) = &mut player_block_placement {
-                                            block.col = (block.col as i32 - 1).max(0) as usize;
+                                    let next_turn = next_human_turn(turn, action);
+                                    match (turn, next_turn) {
+                                        (_, HumanTurn::Resigned) => {
+                                            player_block_placement = None;
+                                            resigned = true;
+                                            break 'placement;
                                         }
-                                    }
-                                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Right
-                                    {
-                                        if let Some(block) = &mut player_block_placement {
-                                            block.col = (block.col + 1)
-                                                .min(app.board_widget.board.ncols() - 1);
+                                        (_, HumanTurn::Quitting) => break 'game,
+                                        (_, HumanTurn::ConfirmingQuit) => {
+                                            let notice = String::from("press q again to quit");
+                                            app.notice = Some((Instant::now(), notice));
                                         }
-                                    }
-                                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Up {
-                                        if let Some(block) = &mut player_block_placement {
-                                            block.row = (block.row as i32 - 1).max(0) as usize;
+                                        (HumanTurn::Placing, HumanTurn::Selecting) => {
+                                            player_block_placement = None;
                                         }
-                                    }
-                                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Down
-                                    {
-                                        if let Some(block) = &mut player_block_placement {
-                                            block.row = (block.row + 1)
-                                                .min(app.board_widget.board.nrows() - 1);
+                                        (
+                                            HumanTurn::Selecting | HumanTurn::ConfirmingQuit,
+                                            HumanTurn::Placing,
+                                        ) => {
+                                            // picked again, cycling from the dropped piece
+                                            player_block_placement = Some(BlockPlacement {
+                                                block: player.blocks[block_selection].clone(),
+                                                row: 0,
+                                                col: 0,
+                                                rotation: 0,
+                                                transposition: 0,
+                                            });
                                         }
+                                        _ => {}
                                     }
-                                    if key.kind == KeyEventKind::Press
-                                        && key.code == KeyCode::Char('j')
-                                    {
+                                    turn = next_turn;
+                                    if action == Action::CyclePrev {
                                         if let Some(block_placement) = &mut player_block_placement {
                                             block_selection =
                                                 (block_selection + player.blocks.len() - 1)
@@ -448,9 +1497,7 @@ fn main() -> Result<()> {
                                             }
                                         }
                                     }
-                                    if key.kind == KeyEventKind::Press
-                                        && key.code == KeyCode::Char('k')
-                                    {
+                                    if action == Action::CycleNext {
                                         if let Some(block_placement) = &mut player_block_placement {
                                             block_selection =
                                                 (block_selection + 1) % player.blocks.len();
@@ -460,110 +1507,1056 @@ fn main() -> Result<()> {
                                             }
                                         }
                                     }
-                                    if key.kind == KeyEventKind::Press
-                                        && key.code == KeyCode::Char('t')
-                                    {
+                                    if action == Action::Transpose {
                                         if let Some(block_placement) = &mut player_block_placement {
                                             block_placement.transposition =
                                                 (block_placement.transposition + 1) % 2;
                                         }
                                     }
-                                    if key.kind == KeyEventKind::Press
-                                        && key.code == KeyCode::Char('r')
-                                    {
+                                    if action == Action::Reflect {
+                                        if let Some(block_placement) = &mut player_block_placement {
+                                            block_placement.reflect();
+                                        }
+                                    }
+                                    if action == Action::CycleOrientation {
+                                        if let Some(block_placement) = &mut player_block_placement {
+                                            let piece = OrientedPiece::new(&block_placement.block);
+                                            let index = OrientedPiece::index(
+                                                block_placement.rotation,
+                                                block_placement.transposition,
+                                            );
+                                            (
+                                                block_placement.rotation,
+                                                block_placement.transposition,
+                                            ) = OrientedPiece::rotation_transposition(
+                                                piece.next_distinct(index),
+                                            );
+                                        }
+                                    }
+                                    if action == Action::Rotate {
                                         if let Some(block_placement) = &mut player_block_placement {
                                             block_placement.rotation =
                                                 (block_placement.rotation + 1) % 4;
                                         }
                                     }
-                                    if key.kind == KeyEventKind::Press && key.code == KeyCode::Enter
-                                    {
+                                    if action == Action::ToggleAssist {
+                                        app.assist = !app.assist;
+                                    }
+                                    if action == Action::ToggleCoordinates {
+                                        app.coordinates = !app.coordinates;
+                                    }
+                                    app.move_log_scroll = scroll_move_log(
+                                        app.move_log_scroll,
+                                        action,
+                                        app.move_lines.len(),
+                                        app.move_log_height,
+                                    );
+                                    if action == Action::Suggest {
+                                        if let Some((hand_index, suggestion)) = suggest_move(
+                                            &board,
+                                            player,
+                                            turn_counter == 0,
+                                            &mut GreedyStrategy::default(),
+                                        ) {
+                                            block_selection = hand_index;
+                                            player_block_placement = Some(suggestion);
+                                        }
+                                    }
+                                    if action == Action::Describe {
+                                        app.description = match app.description {
+                                            Some(_) => None,
+                                            None => Some(
+                                                board.describe(player.player_id, turn_counter == 0),
+                                            ),
+                                        };
+                                    }
+                                    if action == Action::Confirm {
                                         if let Some(block_placement) = &mut player_block_placement {
-                                            let (row, col, block) =
-                                                block_placement.as_row_col_block();
-                                            let placement_rule = app.board_widget.board.can_place(
-                                                row,
-                                                col,
-                                                &block,
+                                            let placement_rule = board.can_place_placement(
+                                                block_placement,
                                                 player.player_id,
                                                 turn_counter == 0,
                                             );
                                             if placement_rule.placement_ok() {
-                                                break;
+                                                let stuck = players_left_stuck(
+                                                    &board,
+                                                    block_placement,
+                                                    player.player_id,
+                                                    &players,
+                                                    &players_eliminated,
+                                                );
+                                                if stuck.is_empty()
+                                                    || stuck_warning.as_ref()
+                                                        == Some(block_placement)
+                                                {
+                                                    break 'placement;
+                                                }
+                                                let names: Vec<String> = stuck
+                                                    .iter()
+                                                    .map(|p| p.display_name())
+                                                    .collect();
+                                                let notice = format!(
+                                                    "{} left with no legal moves, confirm again to play it",
+                                                    names.join(", ")
+                                                );
+                                                app.notice = Some((Instant::now(), notice));
+                                                stuck_warning = Some(block_placement.clone());
+                                                continue;
+                                            }
+                                            if app.assist {
+                                                // snap to the nearest legal anchor for the current orientation
+                                                let anchors: Vec<(usize, usize)> = board
+                                                    .legal_placements(
+                                                        &block_placement.block,
+                                                        player.player_id,
+                                                        turn_counter == 0,
+                                                    )
+                                                    .iter()
+                                                    .filter(|position| {
+                                                        position.rotation
+                                                            == block_placement.rotation
+                                                            && position.transposition
+                                                                == block_placement.transposition
+                                                    })
+                                                    .map(|position| (position.row, position.col))
+                                                    .collect();
+                                                if let Some((row, col)) = nearest_anchor(
+                                                    &anchors,
+                                                    (block_placement.row, block_placement.col),
+                                                ) {
+                                                    block_placement.row = row;
+                                                    block_placement.col = col;
+                                                }
                                             }
                                         }
                                     }
                                 }
-                            }
 
-                            if app.block_placement_widget.block_placement != player_block_placement
-                            {
-                                app.block_placement_widget
-                                    .block_placement
-                                    .clone_from(&player_block_placement);
+                                if let Some(block_placement) = &mut player_block_placement {
+                                    // after moves and orientation changes alike
+                                    clamp_anchor(block_placement, board.nrows(), board.ncols());
+                                }
+                                if app.block_placement_widget.block_placement
+                                    != player_block_placement
+                                {
+                                    app.block_placement_widget
+                                        .block_placement
+                                        .clone_from(&player_block_placement);
+                                }
+                                let rendering_start = Instant::now();
+                                terminal.draw(|frame| {
+                                    let area = frame.size();
+                                    let app_view = AppView {
+                                        app: &mut app,
+                                        board: &board,
+                                        player: Some(player),
+                                        players: &players,
+                                    };
+                                    frame.render_widget(app_view, area);
+                                })?;
+                                rendering_time += rendering_start.elapsed();
                             }
+                            player_block_placement
+                        } else {
+                            None
+                        }
+                    } else if let Some(strategy) = strategies.get_mut(&player_id) {
+                        strategy.place(
+                            &board,
+                            player.player_id,
+                            players.as_slice(),
+                            turn_counter == 0,
+                        )
+                    } else {
+                        None
+                    };
+
+                    let coaching_start = Instant::now();
+                    let coaching = match (&mut coach, &block_placement) {
+                        (Some(coach), Some(played)) if player.human => regret(
+                            &board,
+                            player.player_id,
+                            &players,
+                            turn_counter == 0,
+                            played,
+                            coach.as_mut(),
+                        ),
+                        _ => None,
+                    };
+                    // not counted as thinking time either
+                    rendering_time += coaching_start.elapsed();
+
+                    let player: &mut Player = players.get_mut(position).unwrap();
+                    let thinking_time = turn_start.elapsed().saturating_sub(rendering_time);
+                    player.add_thinking_time(thinking_time);
+                    app.placeable_pieces.clear();
+                    app.description = None;
+                    app.move_lines
+                        .push(move_line(player.player_id, block_placement.as_ref()));
+                    app.move_log_scroll = 0;
+                    let mut move_record = if resigned {
+                        resign_move(&mut board, player, &mut players_eliminated)
+                    } else {
+                        play_move(&mut board, player, block_placement, &mut players_eliminated)
+                    }
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                    // given back if the move is undone
+                    move_record.overtime = player.add_overtime(thinking_time, &app.scoring);
+                    if !move_record.occupied_cells.is_empty() {
+                        app.last_placed =
+                            Some((Instant::now(), move_record.occupied_cells.clone()));
+                    }
+                    if move_record.eliminated {
+                        let notice = elimination_notice(&board, player, turn_counter == 0);
+                        app.notice = Some((Instant::now(), notice));
+                    }
+                    if let Some((regret, best)) = coaching {
+                        let notice = if regret > 0 {
+                            format!(
+                                "coach: {} was worth {} more",
+                                move_line(player.player_id, Some(&best)),
+                                regret
+                            )
+                        } else {
+                            String::from("coach: as good as the engine move")
+                        };
+                        app.notice = Some((Instant::now(), notice));
+                    }
+                    let human = player.human;
+                    if !human && !move_record.occupied_cells.is_empty() {
+                        let placed_at = Instant::now();
+                        app.revealing = Some((placed_at, move_record.occupied_cells.clone()));
+                        while placed_at.elapsed() < REVEAL_DURATION {
                             terminal.draw(|frame| {
                                 let area = frame.size();
-                                frame.render_widget(&mut app, area);
+                                let app_view = AppView {
+                                    app: &mut app,
+                                    board: &board,
+                                    player: players.get(position),
+                                    players: &players,
+                                };
+                                frame.render_widget(app_view, area);
                             })?;
+                            std::thread::sleep(Duration::from_millis(16));
                         }
-                        player_block_placement
-                    } else {
-                        None
+                        app.revealing = None;
                     }
-                } else {
-                    GreedyStrategy::place(
-                        &board,
-                        player.player_id,
-                        players.as_slice(),
-                        turn_counter == 0,
-                    )
-                };
 
-                let player: &mut Player = players.get_mut(position).unwrap();
-                if let Some(block_placement) = block_placement {
-                    // remove block from player blocks
-                    let block_index_to_remove = player
-                        .blocks
-                        .iter()
-                        .position(|b| *b == block_placement.block);
-                    if let Some(index) = block_index_to_remove {
-                        player.blocks.remove(index);
+                    if human {
+                        // let the human undo the move before the next player moves
+                        app.reviewing_move = true;
+                        let mut undo = false;
+                        'review: loop {
+                            terminal.draw(|frame| {
+                                let area = frame.size();
+                                let app_view = AppView {
+                                    app: &mut app,
+                                    board: &board,
+                                    player: players.get(position),
+                                    players: &players,
+                                };
+                                frame.render_widget(app_view, area);
+                            })?;
+                            let events = drain_events(Duration::from_millis(16))?;
+                            if needs_full_redraw(&events) {
+                                terminal.clear()?;
+                            }
+                            for input in inputs(&keymap, &events) {
+                                if input == Input::Action(Action::Undo) {
+                                    undo = true;
+                                    break 'review;
+                                }
+                                if input == Input::Action(Action::Confirm) {
+                                    break 'review;
+                                }
+                                if let Input::Action(action) = input {
+                                    app.move_log_scroll = scroll_move_log(
+                                        app.move_log_scroll,
+                                        action,
+                                        app.move_lines.len(),
+                                        app.move_log_height,
+                                    );
+                                }
+                            }
+                        }
+                        app.reviewing_move = false;
+                        if undo {
+                            let player: &mut Player = players.get_mut(position).unwrap();
+                            undo_move(&mut board, player, move_record, &mut players_eliminated);
+                            app.move_lines.pop();
+                            app.last_placed = None;
+                            app.notice = None;
+                            continue;
+                        }
                     }
-
-                    let (row, col, block) = block_placement.as_row_col_block();
-                    board.place(row, col, &block, player_id);
-                } else {
-                    players_eliminated.insert(player_id);
+                    break;
                 }
             }
 
             terminal.draw(|frame| {
                 let area = frame.size();
-                frame.render_widget(&mut app, area);
+                let app_view = AppView {
+                    app: &mut app,
+                    board: &board,
+                    player: players.iter().find(|p| p.player_id == player_id),
+                    players: &players,
+                };
+                frame.render_widget(app_view, area);
             })?;
+
+            // between turns, computer turns included: quitting and scrolling the move log
+            let events = drain_events(Duration::from_millis(16))?;
+            if needs_full_redraw(&events) {
+                terminal.clear()?;
+            }
+            for input in inputs(&keymap, &events) {
+                match input {
+                    Input::Action(Action::Quit) => break 'game,
+                    Input::Action(action) => {
+                        app.move_log_scroll = scroll_move_log(
+                            app.move_log_scroll,
+                            action,
+                            app.move_lines.len(),
+                            app.move_log_height,
+                        );
+                    }
+                    Input::Pointer { .. } => {}
+                }
+            }
         }
 
         turn_counter += 1;
 
-        if event::poll(Duration::from_millis(16))? {
-            if let event::Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
-                    break;
-                }
-            }
+        if all_eliminated(&players, &players_eliminated) {
+            // game over, or stuck in a puzzle: straight to the summary
+            break;
         }
     }
 
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
 
+    if let Some(path) = arg_value(&args, "--svg") {
+        std::fs::write(path, board.to_svg(SVG_CELL_PX, &app.theme))?;
+    }
+    if app.game_mode == GameMode::Puzzle {
+        println!("puzzle over: {} cells placed", board.count_cells(1));
+    }
     for player in players {
+        let strategy_name = match strategies.get(&player.player_id) {
+            Some(strategy) if !player.human => format!(" ({})", strategy.name()),
+            _ => String::new(),
+        };
         println!(
-            "player: {}. left: {}",
-            player.player_id,
-            player.blocks.iter().map(|b| b.cells()).sum::<usize>()
+            "{}{}. left: {}. score: {}. thinking time: {:.1}s",
+            player.display_name(),
+            strategy_name,
+            player.remaining_cells(),
+            board.score_estimate(&player, &game_config.scoring),
+            player.thinking_time.as_secs_f32()
         );
     }
+    println!("seed: {}", seed);
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use crate::block::Block;
+    use crate::board::Board;
+    use crate::config::ConfigFile;
+    use crate::game::{play_move, GameConfig};
+    use crate::input::Action;
+    use crate::strategy::{BlockPlacement, Player};
+    use crate::strategy::{Personality, ScoringConfig};
+    use crate::theme::Theme;
+    use crate::{
+        all_eliminated, bar_length, board_region_size, cell_edges, clamp_anchor, computer_strategy,
+        coordinate_gutters, cursor_to_anchor, edit_name, elimination_notice, game_config,
+        move_anchor, move_line, must_pass, nearest_anchor, next_human_turn, next_personality,
+        parse_hex_color, parse_overtime, parse_puzzle, parse_scoring, players_left_stuck,
+        quick_start_players, scroll_move_log, selected_players, should_flash, should_reveal_cell,
+        terminal_too_small, theme_config, visible_moves, BoardWidget, HumanTurn,
+        PlayerSelectionItem, PlayerSelectionList, PlayerSelectionStatus, PlayerWidget,
+        MAX_NAME_LENGTH, ROW_LABEL_WIDTH,
+    };
+    use ratatui::buffer::Buffer;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::layout::Rect;
+    use ratatui::style::Color;
+    use ratatui::widgets::Widget;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn test_nearest_anchor() {
+        let candidates = vec![(0, 0), (5, 5), (2, 7), (9, 9)];
+        assert_eq!(nearest_anchor(&candidates, (4, 6)), Some((5, 5)));
+        assert_eq!(nearest_anchor(&candidates, (1, 8)), Some((2, 7)));
+        assert_eq!(nearest_anchor(&candidates, (0, 1)), Some((0, 0)));
+        assert_eq!(nearest_anchor(&candidates, (9, 9)), Some((9, 9)));
+        assert_eq!(nearest_anchor(&[], (3, 3)), None);
+    }
+
+    #[test]
+    fn test_cursor_to_anchor() {
+        let board_area = Rect::new(2, 1, 40, 20);
+        assert_eq!(cursor_to_anchor((2, 1), board_area, 20, 20), Some((0, 0)));
+        // both terminal columns of a cell map to the cell
+        assert_eq!(cursor_to_anchor((8, 4), board_area, 20, 20), Some((3, 3)));
+        assert_eq!(cursor_to_anchor((9, 4), board_area, 20, 20), Some((3, 3)));
+        assert_eq!(
+            cursor_to_anchor((41, 20), board_area, 20, 20),
+            Some((19, 19))
+        );
+        // out of the board area, e.g. a click on the hand or the status line
+        assert_eq!(cursor_to_anchor((0, 0), board_area, 20, 20), None);
+        assert_eq!(cursor_to_anchor((1, 5), board_area, 20, 20), None);
+        assert_eq!(cursor_to_anchor((42, 5), board_area, 20, 20), None);
+        assert_eq!(cursor_to_anchor((10, 21), board_area, 20, 20), None);
+        // clamped to the board in a larger area
+        assert_eq!(cursor_to_anchor((41, 5), board_area, 10, 10), Some((4, 9)));
+    }
+
+    #[test]
+    fn test_clamp_anchor() {
+        let mut block_placement = BlockPlacement {
+            block: Block::from_str("#####").unwrap(),
+            row: 19,
+            col: 19,
+            rotation: 0,
+            transposition: 0,
+        };
+        clamp_anchor(&mut block_placement, 20, 20);
+        assert_eq!((block_placement.row, block_placement.col), (19, 15));
+
+        // rotated upright near the bottom edge
+        block_placement.rotation = 1;
+        clamp_anchor(&mut block_placement, 20, 20);
+        assert_eq!((block_placement.row, block_placement.col), (15, 15));
+
+        // away from the edges the anchor is kept
+        block_placement.row = 3;
+        block_placement.col = 4;
+        clamp_anchor(&mut block_placement, 20, 20);
+        assert_eq!((block_placement.row, block_placement.col), (3, 4));
+    }
+
+    #[test]
+    fn test_move_anchor() {
+        let mut block_placement = BlockPlacement {
+            block: Block::from_str("##").unwrap(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        let mut press = |actions: &[Action]| {
+            for movement in actions.iter().filter_map(|action| action.movement()) {
+                move_anchor(&mut block_placement, movement, 4, 4);
+            }
+            (block_placement.row, block_placement.col)
+        };
+        // each key is clamped before the next one
+        assert_eq!(press(&[Action::MoveLeft, Action::MoveRight]), (0, 1));
+        assert_eq!(
+            press(&[Action::MoveRight, Action::MoveRight, Action::MoveLeft]),
+            (0, 1)
+        );
+        assert_eq!(
+            press(&[Action::MoveUp, Action::MoveDown, Action::MoveDown]),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn test_cell_edges() {
+        let mut board = Board::new(4, 4);
+        board
+            .place(0, 0, &Block::from_str("##").unwrap(), 1)
+            .unwrap();
+        board
+            .place(1, 0, &Block::from_str("#").unwrap(), 2)
+            .unwrap();
+
+        assert_eq!(cell_edges(&board, 0, 0), (false, true));
+        assert_eq!(cell_edges(&board, 0, 1), (true, true));
+        assert_eq!(cell_edges(&board, 1, 0), (true, true));
+        // free cells next to free cells
+        assert_eq!(cell_edges(&board, 2, 2), (false, false));
+        // board borders are not edges
+        assert_eq!(cell_edges(&board, 3, 3), (false, false));
+    }
+
+    #[test]
+    fn test_players_left_stuck() {
+        let board = Board::from_str(
+            "1...\n\
+             ....\n\
+             ....\n\
+             ...2",
+        )
+        .unwrap();
+        let players: Vec<Player> = (1..=3)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![Block::from_str("#").unwrap()],
+                ..Default::default()
+            })
+            .collect();
+        // covers (2, 2), the only open corner of player 2
+        let blocking = BlockPlacement {
+            block: Block::from_str("##\n.#").unwrap(),
+            row: 1,
+            col: 1,
+            rotation: 0,
+            transposition: 0,
+        };
+        let stuck = players_left_stuck(&board, &blocking, 1, &players, &HashSet::new());
+        // player 3 can still open in a free corner
+        assert_eq!(
+            stuck.iter().map(|p| p.player_id).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert!(players_left_stuck(&board, &blocking, 1, &players, &HashSet::from([2])).is_empty());
+    }
+
+    #[test]
+    fn test_player_widget_size_groups() {
+        let player = Player {
+            player_id: 1,
+            blocks: ["##", "#", "#\n#", "#\n#\n#", "###"]
+                .map(|s| Block::from_str(s).unwrap())
+                .to_vec(),
+            ..Default::default()
+        };
+        let area = Rect::new(0, 0, 20, 14);
+        let mut buffer = Buffer::empty(area);
+        PlayerWidget {
+            player: &player,
+            placeable: &[true, false, true],
+            theme: &Theme::default(),
+        }
+        .render(area, &mut buffer);
+        let line = |y: u16| {
+            (0..area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect::<String>()
+        };
+        assert!(line(0).starts_with("size 1"));
+        // the monomino is not placeable
+        assert_eq!(buffer.get(0, 1).fg, Color::DarkGray);
+        assert!(line(3).starts_with("size 2"));
+        // both dominoes on the same row, in hand order
+        assert_eq!(line(4).trim_end(), "████  ██");
+        // pieces are centered in same size slots
+        assert!(line(7).starts_with("size 3"));
+        assert_eq!(line(8).trim_end(), "  ██");
+        assert_eq!(line(9).trim_end(), "  ██    ██████");
+        assert_eq!(line(10).trim_end(), "  ██");
+        assert_eq!(buffer.get(0, 4).fg, Theme::default().color(1));
+    }
+
+    #[test]
+    fn test_board_widget() {
+        let mut board = Board::new(3, 4);
+        board
+            .place(0, 0, &Block::from_str("##\n##").unwrap(), 1)
+            .unwrap();
+        board
+            .place(2, 2, &Block::from_str("##").unwrap(), 2)
+            .unwrap();
+        let area = Rect::new(0, 0, 10, 4);
+        let mut buffer = Buffer::empty(area);
+        BoardWidget {
+            board: &board,
+            grid_lines: false,
+            highlighted_cells: &[(0, 0)],
+            hidden_cells: &[(2, 3)],
+            theme: &Theme::default(),
+        }
+        .render(area, &mut buffer);
+        let theme = Theme::default();
+        // two terminal columns per board cell
+        for (x, y, color) in [
+            (2, 0, theme.color(1)),
+            (3, 1, theme.color(1)),
+            (4, 2, theme.color(2)),
+            (5, 2, theme.color(2)),
+            (4, 0, theme.color(0)),
+            // hidden cells are drawn free
+            (6, 2, theme.color(0)),
+        ] {
+            assert_eq!(buffer.get(x, y).symbol(), "█");
+            assert_eq!(buffer.get(x, y).fg, color, "at ({}, {})", x, y);
+        }
+        assert_eq!(buffer.get(0, 0).symbol(), "▓");
+        assert_eq!(buffer.get(1, 0).bg, Color::White);
+        // past the board
+        assert_eq!(buffer.get(8, 0).symbol(), " ");
+        assert_eq!(buffer.get(0, 3).symbol(), " ");
+    }
+
+    #[test]
+    fn test_puzzle_ends_when_stuck() {
+        let (mut board, pieces) = parse_puzzle(
+            "(board: \"..22\\n..22\\n2222\\n2222\", pieces: Some(\"##\\n##\\n\\n#\"))",
+            true,
+        )
+        .unwrap();
+        let mut player = Player {
+            player_id: 1,
+            human: true,
+            blocks: pieces.unwrap(),
+            ..Default::default()
+        };
+        let mut players_eliminated = HashSet::new();
+        assert!(!must_pass(&board, &player, true));
+        let square = BlockPlacement {
+            block: player.blocks[0].clone(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        play_move(
+            &mut board,
+            &mut player,
+            Some(square),
+            &mut players_eliminated,
+        )
+        .unwrap();
+        assert!(!all_eliminated(
+            std::slice::from_ref(&player),
+            &players_eliminated
+        ));
+
+        // no room left for the monomino: the puzzle player passes and the puzzle ends
+        assert!(must_pass(&board, &player, false));
+        play_move(&mut board, &mut player, None, &mut players_eliminated).unwrap();
+        assert!(all_eliminated(
+            std::slice::from_ref(&player),
+            &players_eliminated
+        ));
+        assert_eq!(board.count_cells(1), 4);
+
+        // plain boards keep the --pieces set
+        let (board, pieces) = parse_puzzle("..2\n222\n", false).unwrap();
+        assert_eq!(board.free_cells(), 2);
+        assert_eq!(pieces, None);
+        assert!(parse_puzzle("(board: \"..x\")", true).is_err());
+    }
+
+    #[test]
+    fn test_elimination_notice() {
+        let player = Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#").unwrap()],
+            ..Default::default()
+        };
+        let mut board = Board::from_str("12\n2.").unwrap();
+        // quitting while a move is still possible
+        assert_eq!(
+            elimination_notice(&board, &player, false),
+            "Player 1 eliminated"
+        );
+        board
+            .place(1, 1, &Block::from_str("#").unwrap(), 2)
+            .unwrap();
+        assert!(!board.has_any_legal_placement(&player, false));
+        assert_eq!(
+            elimination_notice(&board, &player, false),
+            "Player 1 eliminated: no legal moves"
+        );
+    }
+
+    #[test]
+    fn test_quick_start_players() {
+        let players = quick_start_players(3, &Block::default_block_set());
+        assert_eq!(
+            players
+                .iter()
+                .map(|p| (p.player_id, p.human))
+                .collect::<Vec<_>>(),
+            vec![(1, true), (2, false), (3, false)]
+        );
+        assert!(players
+            .iter()
+            .all(|p| p.blocks == Block::default_block_set()));
+        assert_eq!(quick_start_players(1, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_selected_players() {
+        let mut items = PlayerSelectionList::default().items;
+        items[1].status = PlayerSelectionStatus::Human;
+        items[2].status = PlayerSelectionStatus::NotSelected;
+        let players = selected_players(&items, &Block::default_block_set());
+        assert_eq!(
+            players
+                .iter()
+                .map(|p| (p.player_id, p.human))
+                .collect::<Vec<_>>(),
+            vec![(1, false), (2, true), (4, false)]
+        );
+        // every row not selected: the game must not start
+        let items: Vec<PlayerSelectionItem> = (1..=4)
+            .map(|player_id| PlayerSelectionItem {
+                status: PlayerSelectionStatus::NotSelected,
+                player_id,
+                name: Player::default_name(player_id),
+                personality: None,
+            })
+            .collect();
+        assert!(selected_players(&items, &Block::default_block_set()).is_empty());
+    }
+
+    #[test]
+    fn test_computer_presets() {
+        let mut personality = None;
+        let mut presets = vec![];
+        loop {
+            personality = next_personality(personality);
+            match personality {
+                Some(personality) => presets.push(personality),
+                None => break,
+            }
+        }
+        assert_eq!(presets, Personality::ALL);
+
+        let mut items = PlayerSelectionList::default().items;
+        items[1].personality = Some(Personality::Aggressive);
+        assert_eq!(
+            computer_strategy(&items, 2, "greedy", 7).unwrap().name(),
+            Personality::Aggressive.strategy(7).name()
+        );
+        assert_eq!(
+            computer_strategy(&items, 1, "maximin", 7).unwrap().name(),
+            "Maximin"
+        );
+        assert!(computer_strategy(&items, 1, "unknown", 7).is_none());
+    }
+
+    #[test]
+    fn test_edit_name() {
+        let mut items = PlayerSelectionList::default().items;
+        assert_eq!(items[1].name, "Player 2");
+        items[1].status = PlayerSelectionStatus::Human;
+
+        let mut name = String::new();
+        assert!(edit_name(&mut name, KeyCode::Char('A')));
+        assert!(edit_name(&mut name, KeyCode::Char('x')));
+        assert!(edit_name(&mut name, KeyCode::Char('y')));
+        assert!(edit_name(&mut name, KeyCode::Backspace));
+        assert!(edit_name(&mut name, KeyCode::Left));
+        assert_eq!(name, "Ax");
+        assert!(!edit_name(&mut name, KeyCode::Enter));
+        assert_eq!(name, "Ax");
+        for _ in 0..20 {
+            edit_name(&mut name, KeyCode::Backspace);
+        }
+        assert_eq!(name, "");
+        for _ in 0..20 {
+            edit_name(&mut name, KeyCode::Char('z'));
+        }
+        assert_eq!(name.len(), MAX_NAME_LENGTH);
+
+        items[1].name = String::from("Ada");
+        let players = selected_players(&items, &Block::default_block_set());
+        assert_eq!(players[1].name, "Ada");
+        assert_eq!(players[1].display_name(), "Ada");
+        assert_eq!(players[0].display_name(), "Player 1");
+    }
+
+    #[test]
+    fn test_all_eliminated() {
+        let players = quick_start_players(3, &Block::default_block_set());
+        let mut players_eliminated = HashSet::new();
+        assert!(!all_eliminated(&players, &players_eliminated));
+        players_eliminated.insert(2);
+        players_eliminated.insert(3);
+        assert!(!all_eliminated(&players, &players_eliminated));
+        players_eliminated.insert(1);
+        assert!(all_eliminated(&players, &players_eliminated));
+        // the puzzle player alone
+        assert!(all_eliminated(&players[..1], &HashSet::from([1])));
+        assert!(!all_eliminated(&players[..1], &HashSet::from([2])));
+    }
+
+    #[test]
+    fn test_next_human_turn() {
+        // quitting drops the held piece, then asks for confirmation
+        assert_eq!(
+            next_human_turn(HumanTurn::Placing, Action::Quit),
+            HumanTurn::Selecting
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::Selecting, Action::Quit),
+            HumanTurn::ConfirmingQuit
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::ConfirmingQuit, Action::Quit),
+            HumanTurn::Quitting
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::ConfirmingQuit, Action::Confirm),
+            HumanTurn::Selecting
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::Selecting, Action::Confirm),
+            HumanTurn::Selecting
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::Selecting, Action::CycleNext),
+            HumanTurn::Placing
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::ConfirmingQuit, Action::CyclePrev),
+            HumanTurn::Placing
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::Placing, Action::Rotate),
+            HumanTurn::Placing
+        );
+        // only resigning eliminates the player
+        assert_eq!(
+            next_human_turn(HumanTurn::Placing, Action::Resign),
+            HumanTurn::Resigned
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::ConfirmingQuit, Action::Resign),
+            HumanTurn::Resigned
+        );
+        assert_eq!(
+            next_human_turn(HumanTurn::Resigned, Action::CycleNext),
+            HumanTurn::Resigned
+        );
+    }
+
+    #[test]
+    fn test_board_region_size() {
+        assert_eq!(board_region_size(20, 20), (40, 20));
+        assert_eq!(board_region_size(14, 14), (28, 14));
+        assert_eq!(board_region_size(5, 9), (18, 5));
+        assert_eq!(board_region_size(30, 40_000), (u16::MAX, 30));
+    }
+
+    #[test]
+    fn test_move_line() {
+        let placement = BlockPlacement {
+            block: Block::from_str("_#\n##\n_#\n_#").unwrap(),
+            row: 3,
+            col: 7,
+            rotation: 2,
+            transposition: 0,
+        };
+        assert_eq!(move_line(2, Some(&placement)), "P2: Y-pentomino @ (3,7) r2");
+        let transposed = BlockPlacement {
+            block: Block::from_str("#").unwrap(),
+            rotation: 0,
+            transposition: 1,
+            ..placement.clone()
+        };
+        assert_eq!(move_line(1, Some(&transposed)), "P1: monomino @ (3,7) r0t");
+        let custom = BlockPlacement {
+            block: Block::from_str("###\n###").unwrap(),
+            ..placement
+        };
+        assert_eq!(move_line(4, Some(&custom)), "P4: custom piece @ (3,7) r2");
+        assert_eq!(move_line(3, None), "P3: pass");
+
+        assert_eq!(visible_moves(10, 4, 0), 6..10);
+        assert_eq!(visible_moves(10, 4, 3), 3..7);
+        // scrolled back to the first moves
+        assert_eq!(visible_moves(10, 4, 9), 0..4);
+        assert_eq!(visible_moves(2, 4, 1), 0..2);
+        assert_eq!(visible_moves(0, 4, 0), 0..0);
+    }
+
+    #[test]
+    fn test_scroll_move_log() {
+        assert_eq!(scroll_move_log(0, Action::ScrollLogUp, 10, 4), 1);
+        assert_eq!(scroll_move_log(1, Action::ScrollLogDown, 10, 4), 0);
+        assert_eq!(scroll_move_log(0, Action::ScrollLogDown, 10, 4), 0);
+        assert_eq!(scroll_move_log(3, Action::Rotate, 10, 4), 3);
+        // stops once the first moves are shown
+        assert_eq!(scroll_move_log(6, Action::ScrollLogUp, 10, 4), 6);
+        assert_eq!(visible_moves(10, 4, 6), 0..4);
+        assert_eq!(scroll_move_log(0, Action::ScrollLogUp, 3, 4), 0);
+    }
+
+    #[test]
+    fn test_coordinate_gutters() {
+        let area = Rect::new(10, 5, 40 + ROW_LABEL_WIDTH, 21);
+        let (cells, labels) = coordinate_gutters(area, 20, 20);
+        assert_eq!(cells, Rect::new(13, 6, 40, 20));
+        assert_eq!(labels.len(), 40);
+        assert!(labels.contains(&(10, 6, String::from(" 0"))));
+        assert!(labels.contains(&(10, 25, String::from("19"))));
+        // column 12 label above its left half
+        assert!(labels.contains(&(37, 5, String::from("2"))));
+        for (x, y, label) in &labels {
+            let label_area = Rect::new(*x, *y, label.len() as u16, 1);
+            assert!(!label_area.intersects(cells), "{:?} over the board", label);
+            assert_eq!(label_area.intersection(area), label_area);
+        }
+
+        // clipped: only the labels of the visible cells
+        let area = Rect::new(0, 0, 10 + ROW_LABEL_WIDTH, 4);
+        let (cells, labels) = coordinate_gutters(area, 20, 20);
+        assert_eq!(cells, Rect::new(3, 1, 10, 3));
+        assert_eq!(labels.len(), 3 + 5);
+        for (x, y, label) in &labels {
+            let label_area = Rect::new(*x, *y, label.len() as u16, 1);
+            assert!(!label_area.intersects(cells));
+            assert_eq!(label_area.intersection(area), label_area);
+        }
+    }
+
+    #[test]
+    fn test_parse_scoring() {
+        assert_eq!(parse_scoring("15,5"), Ok(ScoringConfig::default()));
+        assert_eq!(
+            parse_scoring("20, 0"),
+            Ok(ScoringConfig {
+                all_placed_bonus: 20,
+                monomino_last_bonus: 0,
+                ..Default::default()
+            })
+        );
+        assert!(parse_scoring("15").is_err());
+        assert!(parse_scoring("15,x").is_err());
+    }
+
+    #[test]
+    fn test_parse_overtime() {
+        assert_eq!(parse_overtime("10,1"), Ok((Duration::from_secs(10), 1)));
+        assert_eq!(parse_overtime("30, 5"), Ok((Duration::from_secs(30), 5)));
+        assert!(parse_overtime("10").is_err());
+        assert!(parse_overtime("-1,1").is_err());
+        assert!(parse_overtime("10,x").is_err());
+    }
+
+    #[test]
+    fn test_game_config_from_file() {
+        let config_file = ConfigFile::from_ron(
+            "(
+                colors: {2: \"00ff00\", 4: \"ff8000\"},
+                turn_order: [3, 1],
+                scoring: Some(\"20,0\"),
+                overtime: Some(\"10,1\"),
+            )",
+        )
+        .unwrap();
+        let config = game_config(&[], &config_file).unwrap();
+        assert_eq!(config.turn_order, vec![3, 1]);
+        assert_eq!(
+            config.scoring,
+            ScoringConfig {
+                all_placed_bonus: 20,
+                monomino_last_bonus: 0,
+                turn_budget: Some(Duration::from_secs(10)),
+                overtime_penalty: 1,
+                contiguity_bonus: 0,
+            }
+        );
+        let theme = theme_config(&[], &config_file).unwrap();
+        assert_eq!(theme.color(2), Color::Rgb(0, 255, 0));
+        assert_eq!(theme.color(4), Color::Rgb(255, 128, 0));
+        assert_eq!(theme.color(1), Theme::default().color(1));
+
+        // flags override the file values they set, the others are kept
+        let args = [
+            "blockus-rs",
+            "--scoring",
+            "30,5",
+            "--color4",
+            "0000ff",
+            "--turn-order",
+            "2",
+        ]
+        .map(String::from);
+        let config = game_config(&args, &config_file).unwrap();
+        assert_eq!(config.turn_order, vec![2]);
+        assert_eq!(config.scoring.all_placed_bonus, 30);
+        assert_eq!(config.scoring.monomino_last_bonus, 5);
+        assert_eq!(config.scoring.turn_budget, Some(Duration::from_secs(10)));
+        let theme = theme_config(&args, &config_file).unwrap();
+        assert_eq!(theme.color(2), Color::Rgb(0, 255, 0));
+        assert_eq!(theme.color(4), Color::Rgb(0, 0, 255));
+        // the overrides reach the exported image too
+        let mut board = Board::new(2, 2);
+        board
+            .place(0, 0, &Block::from_str("#").unwrap(), 4)
+            .unwrap();
+        assert!(board.to_svg(10, &theme).contains("fill=\"#0000ff\""));
+
+        let default_config = game_config(&[], &ConfigFile::default()).unwrap();
+        assert_eq!(default_config, GameConfig::default());
+        let config_file = ConfigFile {
+            scoring: Some(String::from("x")),
+            ..Default::default()
+        };
+        assert!(game_config(&[], &config_file).is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("ff8000"), Ok((255, 128, 0)));
+        assert_eq!(parse_hex_color("00A0fF"), Ok((0, 160, 255)));
+        assert!(parse_hex_color("ff800").is_err());
+        assert!(parse_hex_color("ff80000").is_err());
+        assert!(parse_hex_color("#ff800").is_err());
+        assert!(parse_hex_color("gg8000").is_err());
+        assert!(parse_hex_color("+f8000").is_err());
+        assert!(parse_hex_color("ÿ8000").is_err());
+    }
+
+    #[test]
+    fn test_bar_length() {
+        assert_eq!(bar_length(0, 89, 20), 0);
+        assert_eq!(bar_length(89, 89, 20), 20);
+        assert_eq!(bar_length(45, 90, 20), 10);
+        // rounded down
+        assert_eq!(bar_length(1, 89, 20), 0);
+        assert_eq!(bar_length(88, 89, 20), 19);
+        assert_eq!(bar_length(0, 0, 20), 0);
+    }
+
+    #[test]
+    fn test_should_reveal_cell() {
+        // 4 cells over 240ms: one more every 60ms
+        let revealed = |elapsed_ms| {
+            (0..4)
+                .filter(|&index| should_reveal_cell(index, Duration::from_millis(elapsed_ms), 4))
+                .count()
+        };
+        assert_eq!(revealed(0), 1);
+        assert_eq!(revealed(59), 1);
+        assert_eq!(revealed(60), 2);
+        assert_eq!(revealed(150), 3);
+        assert_eq!(revealed(180), 4);
+        assert_eq!(revealed(1000), 4);
+        assert!(should_reveal_cell(0, Duration::ZERO, 1));
+    }
+
+    #[test]
+    fn test_should_flash() {
+        let flash_duration = Duration::from_millis(500);
+        assert!(should_flash(Duration::ZERO, flash_duration));
+        assert!(should_flash(Duration::from_millis(499), flash_duration));
+        assert!(!should_flash(Duration::from_millis(500), flash_duration));
+        assert!(!should_flash(Duration::from_secs(2), flash_duration));
+        assert!(!should_flash(Duration::ZERO, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_terminal_too_small() {
+        assert!(!terminal_too_small(Rect::new(0, 0, 80, 24)));
+        assert!(!terminal_too_small(Rect::new(0, 0, 200, 60)));
+        assert!(terminal_too_small(Rect::new(0, 0, 79, 24)));
+        assert!(terminal_too_small(Rect::new(0, 0, 80, 23)));
+        assert!(terminal_too_small(Rect::new(0, 0, 0, 0)));
+    }
+}
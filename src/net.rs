@@ -0,0 +1,452 @@
+//! Two-player game over TCP.
+//!
+//! The server holds the authoritative [`GameEngine`], clients only send moves.
+//! Messages are RON values, one per line: [`ClientMessage`] from clients and
+//! [`ServerMessage`] from the server, boards and blocks being written in their
+//! [`Board::from_str`] and [`Block::from_str`] formats.
+use std::io::{BufRead, BufReader, Error, ErrorKind, Result, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::block::Block;
+use crate::board::Board;
+use crate::game::{verify_game, GameEngine, MoveOutcome};
+use crate::strategy::{BlockPlacement, Player, Strategy};
+
+/// Moves rejected in a row before the server resigns the player.
+const MAX_REJECTED_MOVES: usize = 3;
+
+/// Boards as [`Board::to_compact_string`] single line strings.
+mod board_string {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::board::Board;
+
+    pub fn serialize<S: Serializer>(board: &Board, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&board.to_compact_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Board, D::Error> {
+        Board::from_compact_string(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Blocks as [`Block::from_str`] strings.
+mod block_string {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::block::Block;
+
+    pub fn serialize<S: Serializer>(block: &Block, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&block.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Block, D::Error> {
+        Block::from_str(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "BlockPlacement")]
+struct BlockPlacementDef {
+    #[serde(with = "block_string")]
+    block: Block,
+    row: usize,
+    col: usize,
+    rotation: u8,
+    transposition: u8,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ClientMessage {
+    Place(#[serde(with = "BlockPlacementDef")] BlockPlacement),
+    /// eliminates the player
+    Pass,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ServerMessage {
+    Welcome(u8),
+    Board(#[serde(with = "board_string")] Board),
+    Turn(u8),
+    Placed(u8),
+    Eliminated(u8),
+    /// the last move was rejected, the player is asked again
+    Error(String),
+    Over,
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+/// One line RON value, without the line feed.
+fn encode<T: Serialize>(message: &T) -> String {
+    ron::to_string(message).expect("messages serialize to RON")
+}
+
+fn decode<T: DeserializeOwned>(line: &str) -> Result<T> {
+    ron::from_str(line).map_err(|e| invalid_data(&e.to_string()))
+}
+
+pub(crate) fn encode_move(block_placement: Option<&BlockPlacement>) -> String {
+    encode(&match block_placement {
+        Some(block_placement) => ClientMessage::Place(block_placement.clone()),
+        None => ClientMessage::Pass,
+    })
+}
+
+pub(crate) fn decode_move(line: &str) -> Result<Option<BlockPlacement>> {
+    match decode(line)? {
+        ClientMessage::Place(block_placement) => Ok(Some(block_placement)),
+        ClientMessage::Pass => Ok(None),
+    }
+}
+
+fn send_line(stream: &mut TcpStream, line: &str) -> Result<()> {
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\n")
+}
+
+/// Connection of player `index + 1`.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+fn broadcast(connections: &mut [Connection], message: &ServerMessage) -> Result<()> {
+    let line = encode(message);
+    for connection in connections.iter_mut() {
+        send_line(&mut connection.writer, &line)?;
+    }
+    Ok(())
+}
+
+/// Bind `addr` and host a two-player game on `board`, see [`serve_listener`].
+pub(crate) fn serve(addr: impl ToSocketAddrs, board: Board) -> Result<Board> {
+    serve_listener(TcpListener::bind(addr)?, board)
+}
+
+/// Wait for two clients, the first one to connect plays first, then run the game until
+/// both players are eliminated.
+///
+/// A client disconnecting, or sending [`MAX_REJECTED_MOVES`] rejected moves in a row,
+/// resigns. Once the game is over, its moves are replayed with [`verify_game`] from the
+/// starting position. Returns the final board.
+pub(crate) fn serve_listener(listener: TcpListener, board: Board) -> Result<Board> {
+    let mut connections = vec![];
+    let mut players = vec![];
+    for player_id in 1..=2 {
+        let (mut writer, _) = listener.accept()?;
+        send_line(&mut writer, &encode(&ServerMessage::Welcome(player_id)))?;
+        connections.push(Connection {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        });
+        players.push(Player {
+            player_id,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        });
+    }
+
+    let mut engine = GameEngine::new(board.clone(), players.clone());
+    // accepted moves in play order, a resignation being a pass
+    let mut moves = vec![];
+    let mut rejected_moves = 0;
+    while let Some(player_id) = engine.current_player_id() {
+        broadcast(
+            &mut connections,
+            &ServerMessage::Board(engine.board().clone()),
+        )?;
+        broadcast(&mut connections, &ServerMessage::Turn(player_id))?;
+        let connection = &mut connections[usize::from(player_id) - 1];
+        let mut line = String::new();
+        let outcome = match connection.reader.read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                // disconnected
+                moves.push((player_id, None));
+                engine
+                    .resign(player_id)
+                    .map(|()| MoveOutcome::Eliminated)
+                    .map_err(|e| e.to_string())
+            }
+            Ok(_) => decode_move(line.trim_end())
+                .map_err(|e| e.to_string())
+                .and_then(|block_placement| {
+                    let outcome = engine
+                        .play(player_id, block_placement.clone())
+                        .map_err(|e| e.to_string());
+                    if outcome.is_ok() {
+                        moves.push((player_id, block_placement));
+                    }
+                    outcome
+                }),
+        };
+        let message = match outcome {
+            Ok(MoveOutcome::Placed(_)) => ServerMessage::Placed(player_id),
+            Ok(MoveOutcome::Eliminated) => ServerMessage::Eliminated(player_id),
+            Err(error) => {
+                send_line(
+                    &mut connections[usize::from(player_id) - 1].writer,
+                    &encode(&ServerMessage::Error(error)),
+                )?;
+                rejected_moves += 1;
+                if rejected_moves < MAX_REJECTED_MOVES {
+                    continue;
+                }
+                engine.resign(player_id).map_err(Error::other)?;
+                moves.push((player_id, None));
+                ServerMessage::Eliminated(player_id)
+            }
+        };
+        rejected_moves = 0;
+        broadcast(&mut connections, &message)?;
+    }
+    verify_game(board, players, &moves).map_err(|e| invalid_data(&e.to_string()))?;
+    broadcast(
+        &mut connections,
+        &ServerMessage::Board(engine.board().clone()),
+    )?;
+    broadcast(&mut connections, &ServerMessage::Over)?;
+    Ok(engine.board().clone())
+}
+
+/// A player connected to a game hosted with [`serve`].
+pub(crate) struct Client {
+    pub player_id: u8,
+    /// last board received from the server
+    pub board: Board,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+/// Join the game hosted at `addr`.
+pub(crate) fn connect(addr: impl ToSocketAddrs) -> Result<Client> {
+    let writer = TcpStream::connect(addr)?;
+    let mut client = Client {
+        player_id: 0,
+        board: Board::default(),
+        reader: BufReader::new(writer.try_clone()?),
+        writer,
+    };
+    match client.next_message()? {
+        ServerMessage::Welcome(player_id) => client.player_id = player_id,
+        message => return Err(invalid_data(&format!("unexpected {:?}", message))),
+    }
+    Ok(client)
+}
+
+impl Client {
+    /// Send a move, `None` passes and eliminates the player.
+    pub fn send_move(&mut self, block_placement: Option<&BlockPlacement>) -> Result<()> {
+        send_line(&mut self.writer, &encode_move(block_placement))
+    }
+
+    /// Block until the next server message, keeping [`Client::board`] up to date.
+    pub fn next_message(&mut self) -> Result<ServerMessage> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "server disconnected"));
+        }
+        let message: ServerMessage = decode(line.trim_end())?;
+        if let ServerMessage::Board(board) = &message {
+            self.board = board.clone();
+        }
+        Ok(message)
+    }
+
+    /// Block until the server answers the move this player sent, returns `true` if its
+    /// block was placed.
+    pub fn move_accepted(&mut self) -> Result<bool> {
+        loop {
+            match self.next_message()? {
+                ServerMessage::Placed(player_id) if player_id == self.player_id => return Ok(true),
+                ServerMessage::Eliminated(player_id) if player_id == self.player_id => {
+                    return Ok(false)
+                }
+                ServerMessage::Error(_) => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+
+    /// Block until it is this player turn, returns `false` if the game is over instead.
+    pub fn wait_turn(&mut self) -> Result<bool> {
+        loop {
+            match self.next_message()? {
+                ServerMessage::Turn(player_id) if player_id == self.player_id => return Ok(true),
+                ServerMessage::Over => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Play every turn of `client` with `strategy` until the game is over, returns the final board.
+pub(crate) fn play_remote(mut client: Client, mut strategy: Box<dyn Strategy>) -> Result<Board> {
+    let mut player = Player {
+        player_id: client.player_id,
+        blocks: Block::default_block_set(),
+        ..Default::default()
+    };
+    while client.wait_turn()? {
+        let block_placement = strategy.place(
+            &client.board,
+            player.player_id,
+            std::slice::from_ref(&player),
+            player.last_placed.is_none(),
+        );
+        client.send_move(block_placement.as_ref())?;
+        let Some(block_placement) = block_placement else {
+            continue;
+        };
+        // a rejected block stays in hand, the server asks again
+        if client.move_accepted()? {
+            if let Some(index) = player.hand_index(&block_placement.block) {
+                player.blocks.remove(index);
+            }
+            player.last_placed = Some(block_placement.block);
+        }
+    }
+    Ok(client.board)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use crate::block::Block;
+    use crate::board::Board;
+    use crate::net::{
+        connect, decode_move, encode_move, play_remote, serve_listener, ServerMessage,
+        MAX_REJECTED_MOVES,
+    };
+    use crate::strategy::{strategy_from_name, BlockPlacement};
+
+    #[test]
+    fn test_encode_move() {
+        let block_placement = BlockPlacement {
+            block: Block::from_str("#_\n##").unwrap(),
+            row: 3,
+            col: 4,
+            rotation: 1,
+            transposition: 0,
+        };
+        let line = encode_move(Some(&block_placement));
+        assert_eq!(
+            line,
+            "Place((block:\"#_\\n##\",row:3,col:4,rotation:1,transposition:0))"
+        );
+        assert_eq!(decode_move(&line).unwrap(), Some(block_placement));
+        assert_eq!(encode_move(None), "Pass");
+        assert_eq!(decode_move(&encode_move(None)).unwrap(), None);
+        assert!(decode_move("Place((row:3,col:4))").is_err());
+        // not a block
+        assert!(decode_move("Place((block:\"\",row:3,col:4,rotation:1,transposition:0))").is_err());
+        assert!(decode_move("Move").is_err());
+    }
+
+    #[test]
+    fn test_loopback_game() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve_listener(listener, Board::new(20, 20)));
+
+        let mut first = connect(addr).unwrap();
+        let mut second = connect(addr).unwrap();
+        assert_eq!((first.player_id, second.player_id), (1, 2));
+
+        let monomino = |row, col| BlockPlacement {
+            block: Block::from_str("#").unwrap(),
+            row,
+            col,
+            rotation: 0,
+            transposition: 0,
+        };
+
+        assert!(first.wait_turn().unwrap());
+        // not in a corner: rejected, still player 1 turn
+        first.send_move(Some(&monomino(5, 5))).unwrap();
+        assert!(matches!(
+            first.next_message().unwrap(),
+            ServerMessage::Error(_)
+        ));
+        assert!(first.wait_turn().unwrap());
+        first.send_move(Some(&monomino(0, 0))).unwrap();
+
+        assert!(second.wait_turn().unwrap());
+        assert_eq!(second.board.at_row_col(0, 0), 1);
+        second.send_move(Some(&monomino(19, 19))).unwrap();
+
+        assert!(first.wait_turn().unwrap());
+        first.send_move(None).unwrap();
+        assert!(second.wait_turn().unwrap());
+        second.send_move(None).unwrap();
+
+        assert!(!first.wait_turn().unwrap());
+        assert!(!second.wait_turn().unwrap());
+        let board = server.join().unwrap().unwrap();
+        assert_eq!(first.board, board);
+        assert_eq!(second.board, board);
+        assert_eq!(board.count_cells(1), 1);
+        assert_eq!(board.count_cells(2), 1);
+    }
+
+    #[test]
+    fn test_rejected_moves_resign() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve_listener(listener, Board::new(6, 6)));
+        let mut first = connect(addr).unwrap();
+        let mut second = connect(addr).unwrap();
+
+        // never in a corner
+        let monomino = BlockPlacement {
+            block: Block::from_str("#").unwrap(),
+            row: 3,
+            col: 3,
+            rotation: 0,
+            transposition: 0,
+        };
+        for _ in 0..MAX_REJECTED_MOVES {
+            assert!(first.wait_turn().unwrap());
+            first.send_move(Some(&monomino)).unwrap();
+            assert!(!first.move_accepted().unwrap());
+        }
+        // resigned, the other player keeps playing alone
+        assert!(second.wait_turn().unwrap());
+        second.send_move(None).unwrap();
+        assert!(!first.wait_turn().unwrap());
+        assert!(!second.wait_turn().unwrap());
+        assert_eq!(server.join().unwrap().unwrap(), Board::new(6, 6));
+    }
+
+    #[test]
+    fn test_play_remote() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve_listener(listener, Board::new(8, 8)));
+        let first = connect(addr).unwrap();
+        let second = connect(addr).unwrap();
+        let players: Vec<_> = [first, second]
+            .into_iter()
+            .map(|client| {
+                thread::spawn(move || play_remote(client, strategy_from_name("greedy", 7).unwrap()))
+            })
+            .collect();
+        let boards: Vec<Board> = players
+            .into_iter()
+            .map(|player| player.join().unwrap().unwrap())
+            .collect();
+        let board = server.join().unwrap().unwrap();
+        assert!(boards.iter().all(|b| *b == board));
+        assert!(board.count_cells(1) > 0);
+        assert!(board.count_cells(2) > 0);
+    }
+}
@@ -1,5 +1,12 @@
-use crate::block::Block;
+use crate::block::{Block, OrientedPiece};
 use crate::board::Board;
+use crate::game::{play_move, undo_move};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct BlockPlacement {
@@ -11,20 +18,76 @@ pub(crate) struct BlockPlacement {
 }
 
 impl BlockPlacement {
+    /// The block transposed `transposition` times then rotated clockwise `rotation` times,
+    /// like [`OrientedPiece::get`].
     pub fn as_row_col_block(&self) -> (usize, usize, Block) {
-        let block = if self.transposition == 0 {
-            self.block.clone()
-        } else {
+        let mut block = if self.transposition % 2 == 1 {
             self.block.transpose()
+        } else {
+            self.block.clone()
         };
-        let block = match self.rotation {
-            1 => block.rotate_90(),
-            2 => block.rotate_90().rotate_90(),
-            3 => block.rotate_90().rotate_90().rotate_90(),
-            _ => block,
-        };
+        for _ in 0..self.rotation % 4 {
+            block = block.rotate_90();
+        }
         (self.row, self.col, block)
     }
+
+    /// Mirror the oriented block left to right, like flipping a physical piece over.
+    pub fn reflect(&mut self) {
+        // mirroring is rotating the transposed block: mirroring `R^r T^t` gives
+        // `R T R^r T^t = R^(1 - r) T^(t + 1)` as `T R T` rotates counter-clockwise
+        self.rotation = (5 - self.rotation % 4) % 4;
+        self.transposition = (self.transposition + 1) % 2;
+    }
+
+    /// Board cells covered by the oriented block anchored at `(row, col)`, in row-major order.
+    ///
+    /// Cells may be out of the board, see [`Board::apply`] for a checked placement.
+    pub fn occupied_cells(&self) -> Vec<(usize, usize)> {
+        let (row, col, block) = self.as_row_col_block();
+        (0..block.nrows())
+            .flat_map(|block_row| (0..block.ncols()).map(move |block_col| (block_row, block_col)))
+            .filter(|&(block_row, block_col)| block.cell_at_row_col(block_row, block_col))
+            .map(|(block_row, block_col)| (row + block_row, col + block_col))
+            .collect()
+    }
+}
+
+/// End of game bonuses and penalties, see [`Player::score_with`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ScoringConfig {
+    /// once every block is placed
+    pub all_placed_bonus: i32,
+    /// on top of `all_placed_bonus` when the last placed block is the monomino
+    pub monomino_last_bonus: i32,
+    /// thinking time per turn before overtime, no overtime if `None`
+    pub turn_budget: Option<Duration>,
+    /// points lost per whole second of overtime over the game
+    pub overtime_penalty: i32,
+    /// points per cell of the player largest group of cells connected by a side or a
+    /// corner, rewarding long chains of blocks, see [`Board::score_estimate`]
+    pub contiguity_bonus: i32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig {
+            all_placed_bonus: 15,
+            monomino_last_bonus: 5,
+            turn_budget: None,
+            overtime_penalty: 0,
+            contiguity_bonus: 0,
+        }
+    }
+}
+
+impl ScoringConfig {
+    /// Time spent over the turn budget for a `turn_duration` long turn.
+    pub fn overtime(&self, turn_duration: Duration) -> Duration {
+        self.turn_budget.map_or(Duration::ZERO, |budget| {
+            turn_duration.saturating_sub(budget)
+        })
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -32,21 +95,276 @@ pub(crate) struct Player {
     pub player_id: u8,
     pub human: bool,
     pub blocks: Vec<Block>,
+    pub thinking_time: Duration,
+    /// block of the last move, for the monomino last bonus
+    pub last_placed: Option<Block>,
+    /// chosen in the main menu, see [`Player::display_name`]
+    pub name: String,
+    /// thinking time over the turn budget, see [`ScoringConfig::overtime`]
+    pub overtime: Duration,
+}
+
+impl Player {
+    /// Accumulate the time spent deciding a turn.
+    pub fn add_thinking_time(&mut self, turn_duration: Duration) {
+        self.thinking_time += turn_duration;
+    }
+
+    /// Accumulate the time spent deciding a turn over the `scoring` turn budget, returns
+    /// the overtime of this turn.
+    pub fn add_overtime(&mut self, turn_duration: Duration, scoring: &ScoringConfig) -> Duration {
+        let overtime = scoring.overtime(turn_duration);
+        self.overtime += overtime;
+        overtime
+    }
+
+    /// Name of `player_id` until one is chosen, `"Player N"`.
+    pub fn default_name(player_id: u8) -> String {
+        format!("Player {}", player_id)
+    }
+
+    /// Name shown in the scoreboard and the summary, see [`Player::default_name`].
+    pub fn display_name(&self) -> String {
+        if self.name.is_empty() {
+            Player::default_name(self.player_id)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Index of `block` in hand, matching rotated or transposed copies too.
+    ///
+    /// An exact match is preferred so that identical pieces keep their hand order.
+    pub fn hand_index(&self, block: &Block) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|b| b == block)
+            .or_else(|| self.blocks.iter().position(|b| b.is_same_piece(block)))
+    }
+
+    /// Number of cells of the blocks still in hand.
+    pub fn remaining_cells(&self) -> usize {
+        self.blocks.iter().map(|b| b.cells()).sum()
+    }
+
+    /// Blocks still in hand with their hand index, grouped by number of cells, in hand order
+    /// within a group.
+    pub fn pieces_by_size(&self) -> BTreeMap<usize, Vec<(usize, &Block)>> {
+        let mut pieces: BTreeMap<usize, Vec<(usize, &Block)>> = BTreeMap::new();
+        for (hand_index, block) in self.blocks.iter().enumerate() {
+            pieces
+                .entry(block.cells())
+                .or_default()
+                .push((hand_index, block));
+        }
+        pieces
+    }
+
+    /// Blokus score with the default [`ScoringConfig`].
+    #[cfg(test)]
+    pub fn score(&self) -> i32 {
+        self.score_with(&ScoringConfig::default())
+    }
+
+    /// Blokus score: minus one per cell still in hand, or the `scoring` bonuses once every
+    /// block is placed, minus the overtime penalty.
+    pub fn score_with(&self, scoring: &ScoringConfig) -> i32 {
+        let overtime_seconds = i32::try_from(self.overtime.as_secs()).unwrap_or(i32::MAX);
+        self.placement_score(scoring) - scoring.overtime_penalty.saturating_mul(overtime_seconds)
+    }
+
+    fn placement_score(&self, scoring: &ScoringConfig) -> i32 {
+        if self.blocks.is_empty() {
+            let monomino_last = self
+                .last_placed
+                .as_ref()
+                .is_some_and(|block| block.cells() == 1);
+            if monomino_last {
+                scoring.all_placed_bonus + scoring.monomino_last_bonus
+            } else {
+                scoring.all_placed_bonus
+            }
+        } else {
+            -(self.remaining_cells() as i32)
+        }
+    }
 }
 
 pub(crate) trait Strategy {
     fn place(
+        &mut self,
         board: &Board,
         player_id: u8,
         players: &[Player],
         first_block: bool,
     ) -> Option<BlockPlacement>;
+
+    /// Display name, with the strategy parameters if any, e.g. `"Greedy(100)"`.
+    fn name(&self) -> String;
+}
+
+/// Split a strategy name such as `"minimax:3"` into its kind and optional parameter.
+///
+/// Returns `None` if the parameter is not a valid number.
+fn parse_strategy_name(name: &str) -> Option<(&str, Option<u32>)> {
+    match name.split_once(':') {
+        Some((kind, parameter)) => parameter.parse().ok().map(|p| (kind, Some(p))),
+        None => Some((name, None)),
+    }
+}
+
+/// Ready-made computer player flavor, each composed from the existing strategies.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Personality {
+    /// denies the strongest opponent while keeping its own corners, see [`BlendStrategy`]
+    Aggressive,
+    /// biggest pieces first, see [`GreedyStrategy`]
+    Greedy,
+    /// expands early and fills pockets late, see [`PhaseStrategy`]
+    Balanced,
+    /// any legal move, see [`RandomStrategy`]
+    Wild,
+}
+
+impl Personality {
+    pub const ALL: [Personality; 4] = [
+        Personality::Aggressive,
+        Personality::Greedy,
+        Personality::Balanced,
+        Personality::Wild,
+    ];
+
+    /// Name accepted by [`strategy_from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Personality::Aggressive => "aggressive",
+            Personality::Greedy => "greedy",
+            Personality::Balanced => "balanced",
+            Personality::Wild => "wild",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Personality> {
+        Personality::ALL
+            .into_iter()
+            .find(|personality| personality.name() == name)
+    }
+
+    /// `seed` seeds the wild personality.
+    pub fn strategy(&self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            Personality::Aggressive => Box::new(BlendStrategy {
+                defense: 1,
+                denial: 2,
+            }),
+            Personality::Greedy => Box::new(GreedyStrategy::default()),
+            Personality::Balanced => Box::new(PhaseStrategy),
+            Personality::Wild => Box::new(RandomStrategy::new(seed)),
+        }
+    }
+}
+
+/// Build a strategy from its name, e.g. `"greedy"`, `"random"`, `"minimax:3"` or
+/// `"mcts:100"`, or from the name of a [`Personality`].
+///
+/// `seed` seeds strategies relying on randomness.
+/// Returns `None` for unknown names or unexpected parameters.
+pub(crate) fn strategy_from_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match parse_strategy_name(name)? {
+        ("greedy", None) => Some(Box::new(GreedyStrategy::default())),
+        ("greedy-compact", None) => Some(Box::new(GreedyStrategy {
+            preference: PiecePreference::CompactFirst,
+            ..Default::default()
+        })),
+        ("greedy-flexible", None) => Some(Box::new(GreedyStrategy {
+            preference: PiecePreference::FlexibleFirst,
+            ..Default::default()
+        })),
+        ("random", None) => Some(Box::new(RandomStrategy::new(seed))),
+        ("phase", None) => Some(Box::new(PhaseStrategy)),
+        ("maximin", None) => Some(Box::new(MaximinStrategy)),
+        ("minimax", depth) if depth != Some(0) => Some(Box::new(MinimaxStrategy::new(
+            depth.unwrap_or(DEFAULT_MINIMAX_DEPTH),
+            Some(DEFAULT_MINIMAX_MAX_NODES),
+        ))),
+        ("mcts", rollouts) if rollouts != Some(0) => Some(Box::new(MctsStrategy::new(
+            rollouts.unwrap_or(DEFAULT_MCTS_ROLLOUTS),
+            seed,
+        ))),
+        (name, None) => Personality::from_name(name).map(|personality| personality.strategy(seed)),
+        _ => None,
+    }
+}
+
+/// Order in which [`GreedyStrategy`] tries the pieces of its hand.
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) enum PiecePreference {
+    /// biggest pieces first, then the most compact ones which waste fewer corners
+    #[default]
+    LargestFirst,
+    /// pieces filling most of their bounding box first, see [`Block::area_bounding_ratio`],
+    /// then the biggest
+    CompactFirst,
+    /// pieces with the fewest distinct orientations first, then the biggest: the flexible
+    /// ones are played last, when they fit where others no longer do
+    FlexibleFirst,
+}
+
+impl PiecePreference {
+    /// Sort `blocks` in the order they should be tried.
+    ///
+    /// Stable: otherwise equal blocks keep their hand order.
+    pub fn sort(&self, blocks: &mut [Block]) {
+        match self {
+            PiecePreference::LargestFirst => blocks.sort_by(|b1, b2| {
+                b1.cells()
+                    .cmp(&b2.cells())
+                    .reverse()
+                    .then_with(|| bounding_box_perimeter(b1).cmp(&bounding_box_perimeter(b2)))
+            }),
+            PiecePreference::CompactFirst => blocks.sort_by(|b1, b2| {
+                b1.area_bounding_ratio()
+                    .total_cmp(&b2.area_bounding_ratio())
+                    .reverse()
+                    .then_with(|| b1.cells().cmp(&b2.cells()).reverse())
+            }),
+            PiecePreference::FlexibleFirst => blocks.sort_by_cached_key(|block| {
+                let orientations = OrientedPiece::new(block).distinct.len();
+                (orientations, std::cmp::Reverse(block.cells()))
+            }),
+        }
+    }
 }
 
-pub(crate) struct GreedyStrategy {}
+/// Place the first block in [`PiecePreference`] order at the first legal position found.
+#[derive(Default)]
+pub(crate) struct GreedyStrategy {
+    /// positions examined per block at most, see [`Board::bruteforce_search_place`]
+    pub max_positions: Option<usize>,
+    pub preference: PiecePreference,
+}
 
 impl Strategy for GreedyStrategy {
+    fn name(&self) -> String {
+        let mut parameters = vec![];
+        match self.preference {
+            PiecePreference::LargestFirst => {}
+            PiecePreference::CompactFirst => parameters.push(String::from("compact")),
+            PiecePreference::FlexibleFirst => parameters.push(String::from("flexible")),
+        }
+        if let Some(max_positions) = self.max_positions {
+            parameters.push(max_positions.to_string());
+        }
+        if parameters.is_empty() {
+            String::from("Greedy")
+        } else {
+            format!("Greedy({})", parameters.join(", "))
+        }
+    }
+
     fn place(
+        &mut self,
         board: &Board,
         player_id: u8,
         players: &[Player],
@@ -58,10 +376,14 @@ impl Strategy for GreedyStrategy {
             .collect();
         if let Some(player) = players_with_player_id.first() {
             let mut player_blocks = player.blocks.clone();
-            player_blocks.sort_unstable_by(|b1, b2| b1.cells().cmp(&b2.cells()).reverse());
+            self.preference.sort(&mut player_blocks);
             for block in player_blocks {
-                let mut bruteforce_search =
-                    board.bruteforce_search_place(&block, player_id, first_block);
+                let mut bruteforce_search = board.bruteforce_search_place(
+                    &block,
+                    player_id,
+                    first_block,
+                    self.max_positions,
+                );
 
                 let block_placement_maybe =
                     bruteforce_search
@@ -78,9 +400,1520 @@ impl Strategy for GreedyStrategy {
                 }
                 // else block cannot be placed on the board
             }
+            // a capped search may miss placements, otherwise passing must mean stuck
+            debug_assert!(
+                self.max_positions.is_some() || !has_legal_placement(board, player, first_block),
+                "{} passed with a legal placement left for player {}",
+                self.name(),
+                player_id
+            );
             None
         } else {
             None
         }
     }
 }
+
+/// Whether any block of the hand has a legal placement, to cross-check the strategies when
+/// they pass.
+///
+/// Tries every anchor with the 4 rotations of the block and of its transpose through
+/// [`Board::can_place`], without the orientation deduplication of
+/// [`Board::bruteforce_search_place`] the strategies rely on.
+fn has_legal_placement(board: &Board, player: &Player, first_block: bool) -> bool {
+    player.blocks.iter().any(|block| {
+        [block.clone(), block.transpose()]
+            .into_iter()
+            .flat_map(|block| std::iter::successors(Some(block), |b| Some(b.rotate_90())).take(4))
+            .any(|oriented| {
+                (0..board.nrows()).any(|row| {
+                    (0..board.ncols()).any(|col| {
+                        board
+                            .can_place(row, col, &oriented, player.player_id, first_block)
+                            .placement_ok()
+                    })
+                })
+            })
+    })
+}
+
+fn bounding_box_perimeter(block: &Block) -> usize {
+    let (nrows, ncols) = block.bounding_box();
+    2 * (nrows + ncols)
+}
+
+/// Play a random legal placement among all the player blocks.
+pub(crate) struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        RandomStrategy {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> String {
+        String::from("Random")
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        let player = players.iter().find(|p| p.player_id == player_id)?;
+        let block_placements: Vec<BlockPlacement> = player
+            .blocks
+            .iter()
+            .flat_map(|block| {
+                board
+                    .legal_placements(block, player_id, first_block)
+                    .into_iter()
+                    .map(|possible_block_position| BlockPlacement {
+                        block: block.clone(),
+                        row: possible_block_position.row,
+                        col: possible_block_position.col,
+                        rotation: possible_block_position.rotation,
+                        transposition: possible_block_position.transposition,
+                    })
+            })
+            .collect();
+        block_placements.choose(&mut self.rng).cloned()
+    }
+}
+
+/// Cells of the player on the board after which [`PhaseStrategy`] fully favors filling
+/// pockets: its first 12 pieces with the default set, all pentominoes.
+const PHASE_LATE_GAME_CELLS: usize = 60;
+
+/// Play the largest placeable block, expanding toward the board center early
+/// and filling the smallest empty regions late.
+///
+/// The phase follows the cells the player already placed, so asking again for the same
+/// position gives the same move.
+#[derive(Default)]
+pub(crate) struct PhaseStrategy;
+
+impl PhaseStrategy {
+    /// Phase-weighted score of a placement, the higher the better.
+    ///
+    /// `region_sizes` maps each free cell to the size of its empty region, the largest
+    /// being `largest_region` cells, `late_game_weight` goes from 0 in the opening to 1.
+    fn score(
+        &self,
+        board: &Board,
+        block_placement: &BlockPlacement,
+        region_sizes: &HashMap<(usize, usize), usize>,
+        largest_region: usize,
+        late_game_weight: f32,
+    ) -> f32 {
+        let (row, col, block) = block_placement.as_row_col_block();
+        let (centroid_row, centroid_col) = block.centroid();
+        let center_row = (board.nrows() as f32 - 1.0) / 2.0;
+        let center_col = (board.ncols() as f32 - 1.0) / 2.0;
+        let distance_to_center = (row as f32 + centroid_row - center_row).abs()
+            + (col as f32 + centroid_col - center_col).abs();
+        let centrality = -distance_to_center / (center_row + center_col).max(1.0);
+
+        // size of the empty region the block is filling
+        let region_size = block_placement
+            .occupied_cells()
+            .iter()
+            .map(|cell| region_sizes.get(cell).cloned().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        // relative to the largest region, as much weight as centrality whatever the fill
+        let pocket = -(region_size as f32) / largest_region.max(1) as f32;
+
+        (1.0 - late_game_weight) * centrality + late_game_weight * pocket
+    }
+}
+
+impl Strategy for PhaseStrategy {
+    fn name(&self) -> String {
+        String::from("Phase")
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        let player = players.iter().find(|p| p.player_id == player_id)?;
+        let region_sizes: HashMap<(usize, usize), usize> = board
+            .empty_regions()
+            .iter()
+            .flat_map(|region| region.iter().map(|&cell| (cell, region.len())))
+            .collect();
+        let largest_region = board
+            .largest_empty_region()
+            .map_or(0, |region| region.len());
+        let late_game_weight =
+            (board.count_cells(player_id) as f32 / PHASE_LATE_GAME_CELLS as f32).min(1.0);
+
+        let mut player_blocks = player.blocks.clone();
+        player_blocks.sort_by(|b1, b2| b1.cells().cmp(&b2.cells()).reverse());
+        let mut best: Option<(f32, BlockPlacement)> = None;
+        for block in player_blocks {
+            if best
+                .as_ref()
+                .is_some_and(|(_, placement)| placement.block.cells() > block.cells())
+            {
+                // only the largest placeable blocks are considered
+                break;
+            }
+            for possible_block_position in board.legal_placements(&block, player_id, first_block) {
+                let block_placement = BlockPlacement {
+                    block: block.clone(),
+                    row: possible_block_position.row,
+                    col: possible_block_position.col,
+                    rotation: possible_block_position.rotation,
+                    transposition: possible_block_position.transposition,
+                };
+                let score = self.score(
+                    board,
+                    &block_placement,
+                    &region_sizes,
+                    largest_region,
+                    late_game_weight,
+                );
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_score, _)| score > *best_score)
+                {
+                    best = Some((score, block_placement));
+                }
+            }
+        }
+        best.map(|(_, block_placement)| block_placement)
+    }
+}
+
+/// Order in which a search tries its candidate moves: the most promising first give more
+/// alpha-beta cut-offs.
+pub(crate) trait MoveOrdering {
+    /// Sort `placements` of `player_id`, largest pieces first by default, placements of
+    /// equally large pieces keeping their order.
+    fn order_moves(
+        &self,
+        placements: &mut Vec<BlockPlacement>,
+        _board: &Board,
+        _player_id: u8,
+        _players: &[Player],
+    ) {
+        placements.sort_by_key(|placement| std::cmp::Reverse(placement.block.cells()));
+    }
+}
+
+/// The default [`MoveOrdering`], largest pieces first.
+pub(crate) struct LargestFirst;
+
+impl MoveOrdering for LargestFirst {}
+
+const DEFAULT_MINIMAX_DEPTH: u32 = 2;
+/// keeps a move under a second or so on the standard board
+const DEFAULT_MINIMAX_MAX_NODES: usize = 2_000;
+
+/// Paranoid minimax with alpha-beta pruning: every opponent is assumed to play against
+/// the player.
+///
+/// Opponents play in the `players` order, starting after the player, a player without
+/// any cell on the board is playing its first block.
+pub(crate) struct MinimaxStrategy {
+    /// plies searched, the player move included
+    depth: u32,
+    /// positions evaluated per move at most, the best fully searched move is played
+    /// once the budget is spent, or the first ordered one if none was, unbounded if `None`
+    pub max_nodes: Option<usize>,
+    /// positions evaluated during the last move
+    pub nodes: usize,
+    /// whether the budget ran out during the current search, its value is then partial
+    truncated: bool,
+    /// searched depth and value of exactly evaluated positions, by
+    /// [`Board::symmetric_zobrist_hash`] and player to move: symmetric positions share
+    /// their values as [`evaluate`] and the placement rules are invariant under board
+    /// symmetries
+    transpositions: HashMap<(u64, u8), (u32, i32)>,
+    /// order of the moves tried at each node
+    pub ordering: Box<dyn MoveOrdering>,
+}
+
+impl MinimaxStrategy {
+    pub fn new(depth: u32, max_nodes: Option<usize>) -> Self {
+        MinimaxStrategy {
+            depth,
+            max_nodes,
+            nodes: 0,
+            truncated: false,
+            transpositions: HashMap::new(),
+            ordering: Box::new(LargestFirst),
+        }
+    }
+
+    fn budget_spent(&self) -> bool {
+        self.max_nodes
+            .is_some_and(|max_nodes| self.nodes >= max_nodes)
+    }
+
+    /// Value of the position for `player_id` with `players[position]` to move.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &mut self,
+        board: &mut Board,
+        players: &mut [Player],
+        player_id: u8,
+        position: usize,
+        depth: u32,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        if self.budget_spent() {
+            // the value is discarded by the root, see `truncated`
+            self.truncated = true;
+            return 0;
+        }
+        if depth == 0 {
+            self.nodes += 1;
+            return evaluate(board, player_id, players);
+        }
+        let key = (board.symmetric_zobrist_hash(), players[position].player_id);
+        if let Some(&(searched_depth, value)) = self.transpositions.get(&key) {
+            if searched_depth >= depth {
+                return value;
+            }
+        }
+        let next_position = (position + 1) % players.len();
+        let first_block = board.count_cells(players[position].player_id) == 0;
+        let mut moves = candidate_moves(board, &players[position], first_block);
+        self.ordering
+            .order_moves(&mut moves, board, players[position].player_id, players);
+        if moves.is_empty() {
+            // the player to move passes
+            return self.search(
+                board,
+                players,
+                player_id,
+                next_position,
+                depth - 1,
+                alpha,
+                beta,
+            );
+        }
+
+        let (initial_alpha, initial_beta) = (alpha, beta);
+        let maximizing = players[position].player_id == player_id;
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+        let move_count = moves.len();
+        for (index, block_placement) in moves.into_iter().enumerate() {
+            let Ok(move_record) = play_move(
+                board,
+                &mut players[position],
+                Some(block_placement),
+                &mut HashSet::new(),
+            ) else {
+                continue;
+            };
+            let value = self.search(
+                board,
+                players,
+                player_id,
+                next_position,
+                depth - 1,
+                alpha,
+                beta,
+            );
+            undo_move(
+                board,
+                &mut players[position],
+                move_record,
+                &mut HashSet::new(),
+            );
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(value);
+            } else {
+                best = best.min(value);
+                beta = beta.min(value);
+            }
+            if alpha >= beta {
+                break;
+            }
+            if self.budget_spent() {
+                self.truncated |= index + 1 < move_count;
+                break;
+            }
+        }
+        // bounds from cut-offs or a partial search are not exact values
+        if initial_alpha < best && best < initial_beta && !self.truncated {
+            self.transpositions.insert(key, (depth, best));
+        }
+        best
+    }
+}
+
+/// Legal placements of the player blocks, biggest blocks first.
+fn candidate_moves(board: &Board, player: &Player, first_block: bool) -> Vec<BlockPlacement> {
+    let mut blocks = player.blocks.clone();
+    blocks.sort_by(|b1, b2| b1.cells().cmp(&b2.cells()).reverse());
+    blocks.dedup();
+    blocks
+        .into_iter()
+        .flat_map(|block| {
+            board
+                .legal_placements(&block, player.player_id, first_block)
+                .into_iter()
+                .map(move |position| BlockPlacement {
+                    block: block.clone(),
+                    row: position.row,
+                    col: position.col,
+                    rotation: position.rotation,
+                    transposition: position.transposition,
+                })
+        })
+        .collect()
+}
+
+/// Cells and open corners of `player_id` against the best opponent.
+pub(crate) fn evaluate(board: &Board, player_id: u8, players: &[Player]) -> i32 {
+    let potential = |id: u8| {
+        let cells = board.count_cells(id);
+        (cells + board.open_corners(id, cells == 0).len()) as i32
+    };
+    let best_opponent = players
+        .iter()
+        .filter(|p| p.player_id != player_id)
+        .map(|p| potential(p.player_id))
+        .max()
+        .unwrap_or(0);
+    potential(player_id) - best_opponent
+}
+
+impl Strategy for MinimaxStrategy {
+    fn name(&self) -> String {
+        format!("Minimax({})", self.depth)
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        self.nodes = 0;
+        // hands are not part of the key, only reuse values within a move
+        self.transpositions.clear();
+        let position = players.iter().position(|p| p.player_id == player_id)?;
+        let mut board = board.clone();
+        let mut players = players.to_vec();
+        let next_position = (position + 1) % players.len();
+
+        let mut moves = candidate_moves(&board, &players[position], first_block);
+        self.ordering
+            .order_moves(&mut moves, &board, player_id, &players);
+        let first_move = moves.first().cloned();
+        let mut best: Option<(i32, BlockPlacement)> = None;
+        for block_placement in moves {
+            let Ok(move_record) = play_move(
+                &mut board,
+                &mut players[position],
+                Some(block_placement.clone()),
+                &mut HashSet::new(),
+            ) else {
+                continue;
+            };
+            let alpha = best.as_ref().map_or(i32::MIN, |(value, _)| *value);
+            self.truncated = false;
+            let value = self.search(
+                &mut board,
+                &mut players,
+                player_id,
+                next_position,
+                self.depth.saturating_sub(1),
+                alpha,
+                i32::MAX,
+            );
+            undo_move(
+                &mut board,
+                &mut players[position],
+                move_record,
+                &mut HashSet::new(),
+            );
+            if self.truncated {
+                // not comparable with fully searched moves
+                break;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(best_value, _)| value > *best_value)
+            {
+                best = Some((value, block_placement));
+            }
+            if self.budget_spent() {
+                break;
+            }
+        }
+        best.map(|(_, block_placement)| block_placement)
+            .or(first_move)
+    }
+}
+
+const DEFAULT_MCTS_ROLLOUTS: u32 = 100;
+/// moves shared between the rollouts, the best ones by [`evaluate`] right after the move
+const MCTS_CANDIDATES: usize = 8;
+
+/// Monte Carlo search: the rollouts are shared with UCB1 between the most promising moves,
+/// the most tried one is played.
+///
+/// A rollout plays the move then a random reply of each opponent in the `players` order,
+/// and is won if [`evaluate`] then favors the player.
+pub(crate) struct MctsStrategy {
+    /// rollouts per move
+    rollouts: u32,
+    rng: StdRng,
+}
+
+impl MctsStrategy {
+    pub fn new(rollouts: u32, seed: u64) -> Self {
+        MctsStrategy {
+            rollouts,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Outcome for `players[position]` of random replies to its move: 1 for a win, 0.5 for
+    /// a draw and 0 for a loss.
+    fn rollout(&mut self, board: &mut Board, players: &mut [Player], position: usize) -> f64 {
+        let mut replies = vec![];
+        for offset in 1..players.len() {
+            let opponent = (position + offset) % players.len();
+            let first_block = board.count_cells(players[opponent].player_id) == 0;
+            let reply = candidate_moves(board, &players[opponent], first_block)
+                .choose(&mut self.rng)
+                .cloned();
+            if let Ok(move_record) =
+                play_move(board, &mut players[opponent], reply, &mut HashSet::new())
+            {
+                replies.push((opponent, move_record));
+            }
+        }
+        let value = evaluate(board, players[position].player_id, players);
+        for (opponent, move_record) in replies.into_iter().rev() {
+            undo_move(
+                board,
+                &mut players[opponent],
+                move_record,
+                &mut HashSet::new(),
+            );
+        }
+        match value.cmp(&0) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        }
+    }
+}
+
+impl Strategy for MctsStrategy {
+    fn name(&self) -> String {
+        format!("Mcts({})", self.rollouts)
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        let position = players.iter().position(|p| p.player_id == player_id)?;
+        let mut board = board.clone();
+        let mut players = players.to_vec();
+
+        let mut candidates: Vec<(i32, BlockPlacement)> =
+            candidate_moves(&board, &players[position], first_block)
+                .into_iter()
+                .filter_map(|block_placement| {
+                    let value = board
+                        .clone()
+                        .apply_and_score(&block_placement, player_id, &players)
+                        .ok()?;
+                    Some((value, block_placement))
+                })
+                .collect();
+        // stable: the biggest blocks first among equal values
+        candidates.sort_by_key(|(value, _)| std::cmp::Reverse(*value));
+        candidates.truncate(MCTS_CANDIDATES);
+
+        // wins and rollouts of each candidate
+        let mut stats = vec![(0.0, 0); candidates.len()];
+        for rollout in 0..self.rollouts as usize {
+            let Some(candidate) = (if rollout < stats.len() {
+                // each candidate once first
+                Some(rollout)
+            } else {
+                let total = rollout as f64;
+                (0..stats.len()).max_by(|&i, &j| {
+                    let ucb1 = |(wins, tries): (f64, u32)| {
+                        wins / f64::from(tries) + (2.0 * total.ln() / f64::from(tries)).sqrt()
+                    };
+                    ucb1(stats[i]).total_cmp(&ucb1(stats[j])).then(j.cmp(&i))
+                })
+            }) else {
+                break;
+            };
+            let Ok(move_record) = play_move(
+                &mut board,
+                &mut players[position],
+                Some(candidates[candidate].1.clone()),
+                &mut HashSet::new(),
+            ) else {
+                continue;
+            };
+            let outcome = self.rollout(&mut board, &mut players, position);
+            undo_move(
+                &mut board,
+                &mut players[position],
+                move_record,
+                &mut HashSet::new(),
+            );
+            stats[candidate].0 += outcome;
+            stats[candidate].1 += 1;
+        }
+        // the first of the most tried
+        let best = (0..stats.len()).max_by_key(|&i| (stats[i].1, std::cmp::Reverse(i)))?;
+        Some(candidates.swap_remove(best).1)
+    }
+}
+
+/// Deny the strongest opponent: play the placement leaving the fewest open corners to the
+/// opponent with the most of them, the biggest block first among equal placements.
+#[derive(Default)]
+pub(crate) struct MaximinStrategy;
+
+impl Strategy for MaximinStrategy {
+    fn name(&self) -> String {
+        String::from("Maximin")
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        let player = players.iter().find(|p| p.player_id == player_id)?;
+        let mut board = board.clone();
+        // biggest blocks first: the first minimum wins ties
+        candidate_moves(&board, player, first_block)
+            .into_iter()
+            .min_by_key(|block_placement| {
+                let occupied_cells = board
+                    .apply(block_placement, player_id)
+                    .expect("legal placement");
+                let strongest_opponent_corners = players
+                    .iter()
+                    .filter(|p| p.player_id != player_id)
+                    .map(|p| board.open_corner_count(p.player_id))
+                    .max()
+                    .unwrap_or(0);
+                board.undo(&occupied_cells);
+                strongest_opponent_corners
+            })
+    }
+}
+
+/// Blend of defense and denial: play the placement maximizing `defense` times the open
+/// corners of the player minus `denial` times the open corners of the opponent with the
+/// most of them, the biggest block first among equal placements.
+///
+/// With no `defense`, this is [`MaximinStrategy`].
+pub(crate) struct BlendStrategy {
+    pub defense: i32,
+    pub denial: i32,
+}
+
+impl Strategy for BlendStrategy {
+    fn name(&self) -> String {
+        format!("Blend({}, {})", self.defense, self.denial)
+    }
+
+    fn place(
+        &mut self,
+        board: &Board,
+        player_id: u8,
+        players: &[Player],
+        first_block: bool,
+    ) -> Option<BlockPlacement> {
+        let player = players.iter().find(|p| p.player_id == player_id)?;
+        let mut board = board.clone();
+        // biggest blocks first: the first maximum wins ties
+        candidate_moves(&board, player, first_block)
+            .into_iter()
+            .min_by_key(|block_placement| {
+                let occupied_cells = board
+                    .apply(block_placement, player_id)
+                    .expect("legal placement");
+                let own_corners = board.open_corner_count(player_id) as i32;
+                let strongest_opponent_corners = players
+                    .iter()
+                    .filter(|p| p.player_id != player_id)
+                    .map(|p| board.open_corner_count(p.player_id))
+                    .max()
+                    .unwrap_or(0) as i32;
+                board.undo(&occupied_cells);
+                std::cmp::Reverse(
+                    self.defense * own_corners - self.denial * strongest_opponent_corners,
+                )
+            })
+    }
+}
+
+/// Hand index of the piece `strategy` would play for `player`, along with its placement.
+///
+/// Opponents are not known to `strategy`, only `player` is.
+pub(crate) fn suggest_move(
+    board: &Board,
+    player: &Player,
+    first_block: bool,
+    strategy: &mut dyn Strategy,
+) -> Option<(usize, BlockPlacement)> {
+    let block_placement = strategy.place(
+        board,
+        player.player_id,
+        std::slice::from_ref(player),
+        first_block,
+    )?;
+    let hand_index = player.hand_index(&block_placement.block)?;
+    Some((hand_index, block_placement))
+}
+
+/// The move `strategy` would have played instead of `played`, with the regret of playing
+/// `played`: the [`evaluate`] value for `player_id` after the strategy move minus the value
+/// after `played`, negative when `played` is better.
+///
+/// `None` when the strategy finds no move.
+pub(crate) fn regret(
+    board: &Board,
+    player_id: u8,
+    players: &[Player],
+    first_block: bool,
+    played: &BlockPlacement,
+    strategy: &mut dyn Strategy,
+) -> Option<(i32, BlockPlacement)> {
+    let best = strategy.place(board, player_id, players, first_block)?;
+    let value_after = |block_placement: &BlockPlacement| {
+        board
+            .clone()
+            .apply_and_score(block_placement, player_id, players)
+            .ok()
+    };
+    let regret = value_after(&best)? - value_after(played)?;
+    Some((regret, best))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::block::{Block, OrientedPiece};
+    use crate::board::Board;
+    use crate::game::play_move;
+    use crate::strategy::{
+        candidate_moves, has_legal_placement, parse_strategy_name, regret, strategy_from_name,
+        suggest_move, BlendStrategy, BlockPlacement, GreedyStrategy, LargestFirst, MaximinStrategy,
+        MctsStrategy, MinimaxStrategy, MoveOrdering, Personality, PhaseStrategy, PiecePreference,
+        Player, ScoringConfig, Strategy,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_player_score_with() {
+        let monomino = Block::from_str("#").unwrap();
+        let domino = Block::from_str("##").unwrap();
+        let no_monomino_bonus = ScoringConfig {
+            monomino_last_bonus: 0,
+            ..Default::default()
+        };
+        let mut player = Player {
+            player_id: 1,
+            last_placed: Some(monomino.clone()),
+            ..Default::default()
+        };
+        assert_eq!(player.score(), 20);
+        assert_eq!(player.score_with(&no_monomino_bonus), 15);
+
+        // not the monomino last
+        player.last_placed = Some(domino.clone());
+        assert_eq!(player.score(), 15);
+        assert_eq!(player.score_with(&no_monomino_bonus), 15);
+
+        // blocks left
+        player.blocks = vec![domino];
+        player.last_placed = Some(monomino);
+        assert_eq!(player.score(), -2);
+        assert_eq!(player.score_with(&no_monomino_bonus), -2);
+    }
+
+    #[test]
+    fn test_player_overtime_penalty() {
+        let blitz = ScoringConfig {
+            turn_budget: Some(Duration::from_secs(10)),
+            overtime_penalty: 2,
+            ..Default::default()
+        };
+        assert_eq!(blitz.overtime(Duration::from_secs(4)), Duration::ZERO);
+        assert_eq!(blitz.overtime(Duration::from_secs(10)), Duration::ZERO);
+        assert_eq!(
+            blitz.overtime(Duration::from_millis(12_500)),
+            Duration::from_millis(2_500)
+        );
+        // no budget, no overtime
+        let scoring = ScoringConfig::default();
+        assert_eq!(scoring.overtime(Duration::from_secs(60)), Duration::ZERO);
+
+        let mut player = Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("##").unwrap()],
+            ..Default::default()
+        };
+        for turn_duration in [4_000, 12_500, 11_000, 10_000] {
+            player.add_overtime(Duration::from_millis(turn_duration), &blitz);
+        }
+        assert_eq!(player.overtime, Duration::from_millis(3_500));
+        // 3 whole seconds over the budget
+        assert_eq!(player.score_with(&blitz), -2 - 6);
+        assert_eq!(player.score_with(&scoring), -2);
+
+        player.blocks.clear();
+        assert_eq!(player.score_with(&blitz), 15 - 6);
+    }
+
+    #[test]
+    fn test_player_pieces_by_size() {
+        let mut player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        };
+        let bucket_sizes = |player: &Player| {
+            player
+                .pieces_by_size()
+                .iter()
+                .map(|(&cells, blocks)| (cells, blocks.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            bucket_sizes(&player),
+            vec![(1, 1), (2, 1), (3, 2), (4, 5), (5, 12)]
+        );
+        assert!(player.pieces_by_size().iter().all(|(&cells, blocks)| blocks
+            .iter()
+            .all(|&(hand_index, b)| b.cells() == cells && player.blocks[hand_index] == *b)));
+
+        // the monomino was placed
+        player.blocks.retain(|b| b.cells() != 1);
+        assert_eq!(player.pieces_by_size().keys().next(), Some(&2));
+        assert!(Player::default().pieces_by_size().is_empty());
+    }
+
+    #[test]
+    fn test_player_thinking_time() {
+        let mut player = Player::default();
+        assert_eq!(player.thinking_time, Duration::ZERO);
+        for turn_duration in [
+            Duration::from_millis(1500),
+            Duration::from_millis(250),
+            Duration::from_secs(3),
+        ] {
+            player.add_thinking_time(turn_duration);
+        }
+        assert_eq!(player.thinking_time, Duration::from_millis(4750));
+    }
+
+    #[test]
+    fn test_parse_strategy_name() {
+        assert_eq!(parse_strategy_name("greedy"), Some(("greedy", None)));
+        assert_eq!(parse_strategy_name("minimax:3"), Some(("minimax", Some(3))));
+        assert_eq!(parse_strategy_name("mcts:100"), Some(("mcts", Some(100))));
+        assert_eq!(parse_strategy_name("minimax:three"), None);
+        assert_eq!(parse_strategy_name("minimax:"), None);
+    }
+
+    #[test]
+    fn test_strategy_from_name() {
+        let players = vec![Player {
+            player_id: 1,
+            blocks: Block::default_block_set(),
+            ..Default::default()
+        }];
+        let board = Board::new(20, 20);
+        for name in [
+            "greedy",
+            "random",
+            "phase",
+            "minimax:1",
+            "maximin",
+            "mcts:10",
+        ] {
+            let strategy = strategy_from_name(name, 42);
+            assert!(strategy.is_some());
+            let block_placement = strategy.unwrap().place(&board, 1, &players, true);
+            assert!(block_placement.is_some());
+            let (row, col, block) = block_placement.unwrap().as_row_col_block();
+            assert!(board.can_place(row, col, &block, 1, true).placement_ok());
+        }
+        assert!(strategy_from_name("unknown", 42).is_none());
+        assert!(strategy_from_name("greedy:3", 42).is_none());
+        assert!(strategy_from_name("minimax:0", 42).is_none());
+        assert!(strategy_from_name("mcts:0", 42).is_none());
+        assert_eq!(strategy_from_name("mcts", 42).unwrap().name(), "Mcts(100)");
+        assert_eq!(
+            strategy_from_name("mcts:20", 42).unwrap().name(),
+            "Mcts(20)"
+        );
+        assert!(strategy_from_name("random:abc", 42).is_none());
+    }
+
+    #[test]
+    fn test_personalities() {
+        for personality in Personality::ALL {
+            assert_eq!(
+                Personality::from_name(personality.name()),
+                Some(personality)
+            );
+            assert!(strategy_from_name(personality.name(), 42).is_some());
+            // a few turns of a two player game between presets
+            let mut players: Vec<Player> = (1..=2)
+                .map(|player_id| Player {
+                    player_id,
+                    blocks: Block::default_block_set(),
+                    ..Default::default()
+                })
+                .collect();
+            let mut strategies = [personality.strategy(1), personality.strategy(2)];
+            let mut board = Board::new(14, 14);
+            for turn in 0..4 {
+                for (position, strategy) in strategies.iter_mut().enumerate() {
+                    let player_id = players[position].player_id;
+                    let Some(block_placement) =
+                        strategy.place(&board, player_id, &players, turn == 0)
+                    else {
+                        // passing is only legal without any move left
+                        assert!(!board.has_any_legal_placement(&players[position], turn == 0));
+                        continue;
+                    };
+                    assert!(
+                        board
+                            .can_place_placement(&block_placement, player_id, turn == 0)
+                            .placement_ok(),
+                        "{} played an illegal move",
+                        strategy.name()
+                    );
+                    board.apply(&block_placement, player_id).unwrap();
+                    let index = players[position]
+                        .hand_index(&block_placement.block)
+                        .unwrap();
+                    players[position].blocks.remove(index);
+                }
+            }
+        }
+        assert_eq!(Personality::from_name("unknown"), None);
+        assert!(strategy_from_name("wild:3", 42).is_none());
+    }
+
+    #[test]
+    fn test_block_placement_occupied_cells() {
+        // L4 rotated clockwise once, anchored at (2, 3)
+        let block_placement = BlockPlacement {
+            block: Block::from_str("#_\n#_\n##").unwrap(),
+            row: 2,
+            col: 3,
+            rotation: 1,
+            transposition: 0,
+        };
+        let occupied_cells = block_placement.occupied_cells();
+        assert_eq!(occupied_cells, vec![(2, 3), (2, 4), (2, 5), (3, 3)]);
+        let mut board = Board::new(8, 8);
+        assert_eq!(board.apply(&block_placement, 1), Ok(occupied_cells));
+    }
+
+    #[test]
+    fn test_block_placement_reflect() {
+        let mut block_placement = BlockPlacement {
+            block: Block::from_str("#__\n###").unwrap(),
+            row: 0,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        block_placement.reflect();
+        let (_, _, block) = block_placement.as_row_col_block();
+        assert_eq!(block, Block::from_str("__#\n###").unwrap());
+
+        for index in 0..8 {
+            let (rotation, transposition) = OrientedPiece::rotation_transposition(index);
+            block_placement.rotation = rotation;
+            block_placement.transposition = transposition;
+            let (_, _, block) = block_placement.as_row_col_block();
+            block_placement.reflect();
+            let (_, _, reflected) = block_placement.as_row_col_block();
+            assert_eq!(reflected.nrows(), block.nrows());
+            assert_eq!(reflected.ncols(), block.ncols());
+            for row in 0..block.nrows() {
+                for col in 0..block.ncols() {
+                    assert_eq!(
+                        reflected.cell_at_row_col(row, col),
+                        block.cell_at_row_col(row, block.ncols() - 1 - col)
+                    );
+                }
+            }
+            // reflecting twice gives the orientation back
+            block_placement.reflect();
+            assert_eq!(
+                (block_placement.rotation, block_placement.transposition),
+                (rotation, transposition)
+            );
+        }
+    }
+
+    #[test]
+    fn test_greedy_strategy_prefers_compact_blocks() {
+        let i5 = Block::from_str("#####").unwrap();
+        let p5 = Block::from_str("##\n##\n#_").unwrap();
+        let players = vec![Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#").unwrap(), i5, p5.clone()],
+            ..Default::default()
+        }];
+        let board = Board::new(20, 20);
+        let block_placement = GreedyStrategy::default()
+            .place(&board, 1, &players, true)
+            .unwrap();
+        assert_eq!(block_placement.block, p5);
+    }
+
+    #[test]
+    fn test_greedy_strategy_end_game() {
+        let players = vec![Player {
+            player_id: 1,
+            blocks: vec![
+                Block::from_str("#####").unwrap(),
+                Block::from_str("#").unwrap(),
+            ],
+            ..Default::default()
+        }];
+        // only the monomino fits, in the last free corner
+        let board = Board::from_str(
+            "1.222\n\
+             ..222\n\
+             22222\n\
+             22222\n\
+             22222",
+        )
+        .unwrap();
+        let block_placement = GreedyStrategy::default()
+            .place(&board, 1, &players, false)
+            .unwrap();
+        assert_eq!(block_placement.block, Block::from_str("#").unwrap());
+        assert_eq!((block_placement.row, block_placement.col), (1, 1));
+        assert!(has_legal_placement(&board, &players[0], false));
+
+        // only one orientation of the L tromino fits, both searches must find it
+        let l_players = vec![Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#_\n##").unwrap()],
+            ..Default::default()
+        }];
+        let board = Board::from_str(
+            "12222\n\
+             2..22\n\
+             2.222\n\
+             22222\n\
+             22222",
+        )
+        .unwrap();
+        assert!(has_legal_placement(&board, &l_players[0], false));
+        let block_placement = GreedyStrategy::default()
+            .place(&board, 1, &l_players, false)
+            .unwrap();
+        let mut board_after = board.clone();
+        board_after.apply(&block_placement, 1).unwrap();
+        assert_eq!(board_after.free_cells(), 0);
+        // filled: the pass goes through the debug cross-check
+        assert!(GreedyStrategy::default()
+            .place(&board_after, 1, &l_players, false)
+            .is_none());
+        assert!(!has_legal_placement(&board_after, &l_players[0], false));
+
+        // stuck: the pass goes through the debug cross-check, which must agree
+        let board = Board::from_str(
+            "1.222\n\
+             .2222\n\
+             22.22\n\
+             22222\n\
+             22222",
+        )
+        .unwrap();
+        assert!(GreedyStrategy::default()
+            .place(&board, 1, &players, false)
+            .is_none());
+        assert!(!has_legal_placement(&board, &players[0], false));
+    }
+
+    #[test]
+    fn test_greedy_strategy_search_cap() {
+        let players = vec![Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#").unwrap()],
+            ..Default::default()
+        }];
+        // the top left corner is examined first
+        let board = Board::new(20, 20);
+        let mut strategy = GreedyStrategy {
+            max_positions: Some(1),
+            ..Default::default()
+        };
+        let block_placement = strategy.place(&board, 1, &players, true).unwrap();
+        assert_eq!((block_placement.row, block_placement.col), (0, 0));
+
+        // the only free corner is beyond the cap
+        let mut board = Board::new(20, 20);
+        for (row, col) in [(0, 0), (0, 19), (19, 0)] {
+            board
+                .place(row, col, &Block::from_str("#").unwrap(), 2)
+                .unwrap();
+        }
+        assert!(strategy.place(&board, 1, &players, true).is_none());
+        strategy.max_positions = None;
+        let block_placement = strategy.place(&board, 1, &players, true).unwrap();
+        assert_eq!((block_placement.row, block_placement.col), (19, 19));
+    }
+
+    #[test]
+    fn test_strategy_name() {
+        let names: Vec<String> = ["greedy", "random", "phase", "minimax:3", "maximin"]
+            .iter()
+            .map(|name| strategy_from_name(name, 42).unwrap().name())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Greedy", "Random", "Phase", "Minimax(3)", "Maximin"]
+        );
+        let strategy = GreedyStrategy {
+            max_positions: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(strategy.name(), "Greedy(100)");
+        let strategy = GreedyStrategy {
+            max_positions: Some(100),
+            preference: PiecePreference::CompactFirst,
+        };
+        assert_eq!(strategy.name(), "Greedy(compact, 100)");
+        assert_eq!(
+            strategy_from_name("greedy-flexible", 42).unwrap().name(),
+            "Greedy(flexible)"
+        );
+    }
+
+    #[test]
+    fn test_piece_preference() {
+        let hand: Vec<Block> = ["#", "#####", "#_\n#_\n##", "##\n##", "###\n_#_"]
+            .iter()
+            .map(|s| Block::from_str(s).unwrap())
+            .collect();
+        let sorted = |preference: PiecePreference| -> Vec<usize> {
+            let mut blocks = hand.clone();
+            preference.sort(&mut blocks);
+            blocks
+                .iter()
+                .map(|block| hand.iter().position(|b| b == block).unwrap())
+                .collect()
+        };
+        // I5, then the square with the smallest perimeter among the 4 cells pieces
+        assert_eq!(sorted(PiecePreference::LargestFirst), vec![1, 3, 2, 4, 0]);
+        // the rectangles by size, then L4 and T4 filling 4 / 6 of their box
+        assert_eq!(sorted(PiecePreference::CompactFirst), vec![1, 3, 0, 2, 4]);
+        // 1, 1, 2, 4 and 8 distinct orientations
+        assert_eq!(sorted(PiecePreference::FlexibleFirst), vec![3, 0, 1, 4, 2]);
+    }
+
+    #[test]
+    fn test_maximin_strategy() {
+        let board = Board::from_str(
+            "......\n\
+             ......\n\
+             ..1...\n\
+             ......\n\
+             ...2..\n\
+             ......",
+        )
+        .unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![
+                    Block::from_str("#").unwrap(),
+                    Block::from_str("##").unwrap(),
+                ],
+                ..Default::default()
+            })
+            .collect();
+
+        // the domino takes the (3, 4) corner of player 2
+        let block_placement = MaximinStrategy.place(&board, 1, &players, false).unwrap();
+        let occupied_cells = block_placement.occupied_cells();
+        assert_eq!(occupied_cells, vec![(3, 3), (3, 4)]);
+        let mut after = board.clone();
+        after.apply(&block_placement, 1).unwrap();
+        assert_eq!(board.open_corner_count(2), 4);
+        assert_eq!(after.open_corner_count(2), 3);
+
+        let greedy = GreedyStrategy::default()
+            .place(&board, 1, &players, false)
+            .unwrap();
+        assert_ne!(greedy.occupied_cells(), occupied_cells);
+    }
+
+    #[test]
+    fn test_blend_strategy() {
+        let board = Board::from_str(
+            "11.....\n\
+             .1.....\n\
+             1......\n\
+             1......\n\
+             1.....2\n\
+             .....2.\n\
+             .....22",
+        )
+        .unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![
+                    Block::from_str("#").unwrap(),
+                    Block::from_str("##").unwrap(),
+                ],
+                ..Default::default()
+            })
+            .collect();
+        let corners_after = |block_placement: &BlockPlacement| {
+            let mut after = board.clone();
+            after.apply(block_placement, 1).unwrap();
+            (after.open_corner_count(1), after.open_corner_count(2))
+        };
+
+        // denial only is maximin
+        let maximin = MaximinStrategy.place(&board, 1, &players, false).unwrap();
+        let denial = BlendStrategy {
+            defense: 0,
+            denial: 1,
+        }
+        .place(&board, 1, &players, false);
+        assert_eq!(denial.as_ref(), Some(&maximin));
+
+        // the aggressive preset also keeps its own corners
+        let mut aggressive = BlendStrategy {
+            defense: 1,
+            denial: 2,
+        };
+        let block_placement = aggressive.place(&board, 1, &players, false).unwrap();
+        assert_ne!(block_placement, maximin);
+        assert!(corners_after(&block_placement).0 > corners_after(&maximin).0);
+        assert!(corners_after(&block_placement).1 >= corners_after(&maximin).1);
+        let value = |block_placement: &BlockPlacement| {
+            let (own, opponent) = corners_after(block_placement);
+            own as i32 - 2 * opponent as i32
+        };
+        let best = candidate_moves(&board, &players[0], false)
+            .iter()
+            .map(value)
+            .max()
+            .unwrap();
+        assert_eq!(value(&block_placement), best);
+        assert_eq!(aggressive.name(), "Blend(1, 2)");
+    }
+
+    #[test]
+    fn test_suggest_move() {
+        let mut board = Board::new(6, 6);
+        let mut player = Player {
+            player_id: 1,
+            blocks: Block::default_block_set()[..5].to_vec(),
+            ..Default::default()
+        };
+        for first_block in [true, false] {
+            let (hand_index, block_placement) =
+                suggest_move(&board, &player, first_block, &mut GreedyStrategy::default()).unwrap();
+            assert_eq!(player.blocks[hand_index], block_placement.block);
+            assert!(board
+                .can_place_placement(&block_placement, 1, first_block)
+                .placement_ok());
+            board.apply(&block_placement, 1).unwrap();
+            player.blocks.remove(hand_index);
+        }
+
+        let board = Board::from_str("1.\n..").unwrap();
+        let player = Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("###").unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest_move(&board, &player, false, &mut GreedyStrategy::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_regret() {
+        let board = Board::from_str(
+            "1....\n\
+             .....\n\
+             .....\n\
+             .....\n\
+             ....2",
+        )
+        .unwrap();
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: vec![
+                    Block::from_str("###").unwrap(),
+                    Block::from_str("#").unwrap(),
+                ],
+                ..Default::default()
+            })
+            .collect();
+        let placement = |block: &str, row, col| BlockPlacement {
+            block: Block::from_str(block).unwrap(),
+            row,
+            col,
+            rotation: 0,
+            transposition: 0,
+        };
+        let monomino = placement("#", 1, 1);
+        let (monomino_regret, best) = regret(
+            &board,
+            1,
+            &players,
+            false,
+            &monomino,
+            &mut GreedyStrategy::default(),
+        )
+        .unwrap();
+        // the tromino gets 2 more cells for the same 3 open corners
+        assert_eq!(monomino_regret, 2);
+        let tromino = placement("###", 1, 1);
+        assert_eq!(best, tromino);
+        assert_eq!(
+            regret(
+                &board,
+                1,
+                &players,
+                false,
+                &tromino,
+                &mut GreedyStrategy::default()
+            ),
+            Some((0, tromino))
+        );
+    }
+
+    /// Placements nearest to the top of the board first.
+    struct TopFirst;
+
+    impl MoveOrdering for TopFirst {
+        fn order_moves(
+            &self,
+            placements: &mut Vec<BlockPlacement>,
+            _board: &Board,
+            _player_id: u8,
+            _players: &[Player],
+        ) {
+            placements.sort_by_key(|placement| placement.row);
+        }
+    }
+
+    #[test]
+    fn test_move_ordering() {
+        let board = Board::new(6, 6);
+        let placement = |block: &str, row| BlockPlacement {
+            block: Block::from_str(block).unwrap(),
+            row,
+            col: 0,
+            rotation: 0,
+            transposition: 0,
+        };
+        let placements = vec![
+            placement("##", 3),
+            placement("#", 1),
+            placement("###", 4),
+            placement("##", 0),
+            placement("###", 2),
+        ];
+        let sizes_and_rows = |placements: &[BlockPlacement]| -> Vec<(usize, usize)> {
+            placements
+                .iter()
+                .map(|placement| (placement.block.cells(), placement.row))
+                .collect()
+        };
+
+        let mut largest_first = placements.clone();
+        LargestFirst.order_moves(&mut largest_first, &board, 1, &[]);
+        assert_eq!(
+            sizes_and_rows(&largest_first),
+            vec![(3, 4), (3, 2), (2, 3), (2, 0), (1, 1)]
+        );
+
+        let mut top_first = placements;
+        TopFirst.order_moves(&mut top_first, &board, 1, &[]);
+        assert_eq!(
+            sizes_and_rows(&top_first),
+            vec![(2, 0), (1, 1), (3, 2), (2, 3), (3, 4)]
+        );
+
+        // the ordering changes how the search goes, not which moves are legal
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set()[..4].to_vec(),
+                ..Default::default()
+            })
+            .collect();
+        let mut strategy = MinimaxStrategy::new(2, None);
+        strategy.ordering = Box::new(TopFirst);
+        let block_placement = strategy.place(&board, 1, &players, true).unwrap();
+        assert!(board
+            .can_place_placement(&block_placement, 1, true)
+            .placement_ok());
+    }
+
+    #[test]
+    fn test_mcts_strategy() {
+        let mut board = Board::new(8, 8);
+        let mut players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set(),
+                ..Default::default()
+            })
+            .collect();
+        for turn in 0..3 {
+            for position in 0..players.len() {
+                let player_id = players[position].player_id;
+                let block_placement = MctsStrategy::new(20, 7)
+                    .place(&board, player_id, &players, turn == 0)
+                    .unwrap();
+                // same seed, same move
+                assert_eq!(
+                    MctsStrategy::new(20, 7).place(&board, player_id, &players, turn == 0),
+                    Some(block_placement.clone())
+                );
+                play_move(
+                    &mut board,
+                    &mut players[position],
+                    Some(block_placement),
+                    &mut HashSet::new(),
+                )
+                .unwrap();
+            }
+        }
+        // fewer rollouts than candidates: the best move right after it
+        let best_value = candidate_moves(&board, &players[0], false)
+            .iter()
+            .filter_map(|m| board.clone().apply_and_score(m, 1, &players).ok())
+            .max();
+        let block_placement = MctsStrategy::new(1, 7)
+            .place(&board, 1, &players, false)
+            .unwrap();
+        assert_eq!(
+            board
+                .clone()
+                .apply_and_score(&block_placement, 1, &players)
+                .ok(),
+            best_value
+        );
+    }
+
+    #[test]
+    fn test_minimax_strategy_max_nodes() {
+        let board = Board::new(6, 6);
+        let players: Vec<Player> = (1..=2)
+            .map(|player_id| Player {
+                player_id,
+                blocks: Block::default_block_set()[..4].to_vec(),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut strategy = MinimaxStrategy::new(2, Some(1));
+        let block_placement = strategy.place(&board, 1, &players, true).unwrap();
+        let (row, col, block) = block_placement.as_row_col_block();
+        assert!(board.can_place(row, col, &block, 1, true).placement_ok());
+        let tiny_budget_nodes = strategy.nodes;
+        // no evaluation past the budget
+        assert_eq!(tiny_budget_nodes, 1);
+
+        let mut strategy = MinimaxStrategy::new(2, Some(50));
+        assert!(strategy.place(&board, 1, &players, true).is_some());
+        assert!(strategy.nodes > tiny_budget_nodes);
+        assert!(strategy.nodes <= 50);
+
+        let mut strategy = MinimaxStrategy::new(2, None);
+        assert!(strategy.place(&board, 1, &players, true).is_some());
+        assert!(strategy.nodes > 50);
+    }
+
+    #[test]
+    fn test_phase_strategy() {
+        let board = Board::from_str(
+            ".2......\n\
+             21......\n\
+             ........\n\
+             ........\n\
+             ........\n\
+             ........\n\
+             ........\n\
+             ........",
+        )
+        .unwrap();
+        let players = vec![Player {
+            player_id: 1,
+            blocks: vec![Block::from_str("#").unwrap()],
+            ..Default::default()
+        }];
+
+        // early: the most central corner, asking again does not move on to the late game
+        let mut strategy = PhaseStrategy;
+        for _ in 0..20 {
+            let block_placement = strategy.place(&board, 1, &players, false).unwrap();
+            assert_eq!((block_placement.row, block_placement.col), (2, 2));
+        }
+
+        // late, with 61 cells placed: the isolated top left pocket
+        let board = Board::from_str(
+            ".2........\n\
+             21........\n\
+             ..........\n\
+             ..........\n\
+             1111111111\n\
+             1111111111\n\
+             1111111111\n\
+             1111111111\n\
+             1111111111\n\
+             1111111111",
+        )
+        .unwrap();
+        let block_placement = strategy.place(&board, 1, &players, false).unwrap();
+        assert_eq!((block_placement.row, block_placement.col), (0, 0));
+    }
+}
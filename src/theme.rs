@@ -0,0 +1,35 @@
+use ratatui::style::Color;
+
+/// Colors used to draw the board, as `(red, green, blue)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Theme {
+    /// colors of players 1 to 4
+    pub player_colors: [(u8, u8, u8); 4],
+    /// free cells and unknown players
+    pub background: (u8, u8, u8),
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            player_colors: [(255, 0, 0), (0, 255, 0), (0, 0, 255), (255, 255, 0)],
+            background: (0, 0, 0),
+        }
+    }
+}
+
+impl Theme {
+    pub fn player_rgb(&self, player_id: u8) -> (u8, u8, u8) {
+        usize::from(player_id)
+            .checked_sub(1)
+            .and_then(|i| self.player_colors.get(i))
+            .copied()
+            .unwrap_or(self.background)
+    }
+
+    /// Terminal color of `player_id`, see [`Theme::player_rgb`].
+    pub fn color(&self, player_id: u8) -> Color {
+        let (red, green, blue) = self.player_rgb(player_id);
+        Color::Rgb(red, green, blue)
+    }
+}